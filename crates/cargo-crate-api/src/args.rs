@@ -1,3 +1,5 @@
+use crate::config::Config;
+use crate::filter::Filter;
 use crate::report::Source;
 
 #[derive(clap::Parser)]
@@ -26,15 +28,45 @@ pub struct Api {
     #[clap(short, long, group = "mode")]
     pub diff: bool,
 
+    /// Describe a rule reported by `--diff`
+    #[clap(long, value_name = "ID", group = "mode")]
+    pub explain: Option<String>,
+
+    /// List the rules reported by `--diff`
+    #[clap(long, group = "mode")]
+    pub list_rules: bool,
+
     #[clap(long, value_name = "REF", group = "base")]
     pub git: Option<String>,
 
+    /// API to diff against, from `--api --format json` or rustdoc JSON
+    #[clap(long, value_name = "JSON", group = "base", requires = "after")]
+    pub before: Option<std::path::PathBuf>,
+
+    /// API to diff, from `--api --format json` or rustdoc JSON
+    #[clap(long, value_name = "JSON", requires = "before")]
+    pub after: Option<std::path::PathBuf>,
+
+    /// Revision to diff, rather than the working tree
+    #[clap(long, value_name = "REF", requires = "git")]
+    pub head: Option<String>,
+
     #[clap(long, value_name = "TOML", group = "base")]
     pub path: Option<std::path::PathBuf>,
 
+    /// Published version to diff against, like `foo@1.0.0`, or `foo` for its latest release no newer than the package
     #[clap(long, value_name = "PKG", group = "base")]
     pub registry: Option<String>,
 
+    /// Release tag name to find the base by when none is given, like `v{{version}}`
+    #[clap(
+        long,
+        value_name = "TEMPLATE",
+        multiple_occurrences = true,
+        requires = "diff"
+    )]
+    pub tag_template: Vec<String>,
+
     #[clap(
         short,
         long,
@@ -43,6 +75,58 @@ pub struct Api {
     )]
     pub format: Format,
 
+    /// Only report paths matching the glob, like `mycrate::module::*` (`**` crosses modules)
+    ///
+    /// Changes that aren't tied to a path, like to a dependency's version requirement, are left
+    /// out.
+    #[clap(
+        long,
+        value_name = "GLOB",
+        multiple_occurrences = true,
+        conflicts_with = "dump-raw"
+    )]
+    pub path_filter: Vec<String>,
+
+    /// Only report paths of this kind, like `function` or `struct`
+    ///
+    /// Like `--path-filter`, changes that aren't tied to a path are left out.
+    #[clap(
+        long,
+        value_name = "KIND",
+        parse(try_from_str = parse_kind),
+        multiple_occurrences = true,
+        conflicts_with = "dump-raw"
+    )]
+    pub kind: Vec<crate_api::PathKind>,
+
+    /// Only report changes at or above this severity
+    #[clap(
+        long,
+        value_name = "SEVERITY",
+        parse(try_from_str = parse_severity),
+        possible_values = ["allow", "report", "warn"],
+        requires = "diff"
+    )]
+    pub min_severity: Option<crate_api::diff::Severity>,
+
+    /// Add the changes to the Unreleased section of a Keep a Changelog file
+    ///
+    /// When more than one package is selected, entries start with the package's name.
+    #[clap(long, value_name = "PATH", requires = "diff")]
+    pub changelog: Option<std::path::PathBuf>,
+
+    /// Always rebuild the API of the base, rather than reusing a cached copy
+    #[clap(long)]
+    pub no_cache: bool,
+
+    /// Number of packages to process in parallel
+    #[clap(short, long, value_name = "N", default_value_t = 2)]
+    pub jobs: usize,
+
+    /// Toolchain to generate rustdoc JSON with [default: nightly]
+    #[clap(long, value_name = "TOOLCHAIN")]
+    pub toolchain: Option<String>,
+
     #[clap(flatten)]
     pub manifest: clap_cargo::Manifest,
 
@@ -69,6 +153,31 @@ impl Api {
         }
     }
 
+    pub fn to_config(&self) -> Config {
+        Config {
+            toolchain: self.toolchain.clone(),
+            tag_templates: (!self.tag_template.is_empty()).then(|| self.tag_template.clone()),
+        }
+    }
+
+    pub fn to_filter(&self) -> Result<Filter, globset::Error> {
+        Filter::new(&self.path_filter, &self.kind, self.min_severity)
+    }
+
+    /// Whether packages are selected as a group, rather than naming one
+    ///
+    /// Reports are keyed by package when this is set, regardless of how many packages matched.
+    pub fn selects_workspace(&self, metadata: &cargo_metadata::Metadata) -> bool {
+        let root = metadata
+            .resolve
+            .as_ref()
+            .and_then(|resolve| resolve.root.as_ref());
+        self.workspace.workspace
+            || self.workspace.all
+            || 1 < self.workspace.package.len()
+            || (self.workspace.package.is_empty() && root.is_none())
+    }
+
     pub fn base(&self) -> Option<Source> {
         #[allow(clippy::manual_map)]
         if let Some(git) = self.git.as_ref() {
@@ -83,6 +192,16 @@ impl Api {
     }
 }
 
+fn parse_kind(value: &str) -> Result<crate_api::PathKind, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_owned()))
+        .map_err(|_| format!("unknown kind `{}`", value))
+}
+
+fn parse_severity(value: &str) -> Result<crate_api::diff::Severity, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_owned()))
+        .map_err(|_| format!("unknown severity `{}`", value))
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Mode {
     DumpRaw,
@@ -96,7 +215,33 @@ pub enum Format {
     Pretty,
     #[clap(alias = "markdown")]
     Md,
+    /// Keyed by package name when selecting more than one package, like with `--workspace`, with
+    /// an `error` for each package that failed
     Json,
+    /// Self-contained web page (`--api` and `--diff` only)
+    Html,
+    /// Static analysis results, for code scanning tools (`--diff` only)
+    Sarif,
+    /// Annotations and a job summary for GitHub Actions (`--diff` only)
+    Github,
+    /// Keep a Changelog sections (`--diff` only)
+    Changelog,
+    /// Test results with a failure per kind of breaking change (`--diff` only)
+    Junit,
+    /// One sorted line per public item, for checking into version control (`--api` only)
+    Text,
+}
+
+impl Format {
+    /// Whether the format can be used with `mode`
+    pub fn supports(self, mode: Mode) -> bool {
+        match self {
+            Self::Silent | Self::Pretty | Self::Md | Self::Json => true,
+            Self::Html => mode != Mode::DumpRaw,
+            Self::Sarif | Self::Github | Self::Changelog | Self::Junit => mode == Mode::Diff,
+            Self::Text => mode == Mode::Api,
+        }
+    }
 }
 
 impl Default for Format {
@@ -110,3 +255,29 @@ fn verify_app() {
     use clap::CommandFactory;
     Command::command().debug_assert()
 }
+
+#[test]
+fn selects_workspace_from_flags() {
+    use clap::Parser;
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures");
+    let package = cargo_metadata::MetadataCommand::new()
+        .manifest_path(fixtures.join("dep_upgrade/new/Cargo.toml"))
+        .exec()
+        .unwrap();
+    let virtual_ = cargo_metadata::MetadataCommand::new()
+        .manifest_path(fixtures.join("Cargo.toml"))
+        .exec()
+        .unwrap();
+    let selects = |args: &[&str], metadata: &cargo_metadata::Metadata| {
+        let Command::CrateApi(api) =
+            Command::try_parse_from(["cargo", "crate-api"].iter().chain(args)).unwrap();
+        api.selects_workspace(metadata)
+    };
+
+    assert!(!selects(&[], &package));
+    assert!(!selects(&["--package", "dep_upgrade"], &package));
+    assert!(selects(&["--workspace"], &package));
+    assert!(selects(&["-p", "dep_upgrade", "-p", "addition"], &package));
+    assert!(selects(&[], &virtual_));
+    assert!(!selects(&["--package", "dep_upgrade"], &virtual_));
+}
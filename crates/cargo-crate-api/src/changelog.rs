@@ -0,0 +1,213 @@
+const SECTIONS: &[&str] = &[
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
+/// Insert `sections` into the `## [Unreleased]` section of a Keep a Changelog file
+///
+/// Entries already in the section are skipped, so re-running is harmless.
+pub fn insert_unreleased(
+    changelog: &str,
+    sections: &[(&str, Vec<String>)],
+) -> Result<String, eyre::Report> {
+    let mut lines: Vec<String> = changelog.lines().map(|line| line.to_owned()).collect();
+    let start = lines
+        .iter()
+        .position(|line| is_unreleased_header(line))
+        .ok_or_else(|| eyre::eyre!("Could not find an `## [Unreleased]` section"))?;
+
+    for (heading, entries) in sections {
+        let end = section_end(&lines, start);
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|entry| format!("- {}", entry))
+            .filter(|entry| !lines[start..end].contains(entry))
+            .collect();
+        if entries.is_empty() {
+            continue;
+        }
+
+        let subheader = format!("### {}", heading);
+        match lines[start + 1..end]
+            .iter()
+            .position(|line| line.trim() == subheader)
+        {
+            Some(i) => {
+                let sub_start = start + 1 + i;
+                let sub_end = lines[sub_start + 1..end]
+                    .iter()
+                    .position(|line| line.starts_with("### "))
+                    .map(|j| sub_start + 1 + j)
+                    .unwrap_or(end);
+                let insert_at = after_content(&lines, sub_start, sub_end);
+                lines.splice(insert_at..insert_at, entries);
+            }
+            None => {
+                // Keep subsections in the order Keep a Changelog lists them
+                let order = section_order(heading);
+                let insert_at = lines[start + 1..end]
+                    .iter()
+                    .position(|line| {
+                        line.strip_prefix("### ")
+                            .map(|other| order < section_order(other.trim()))
+                            .unwrap_or(false)
+                    })
+                    .map(|j| start + 1 + j)
+                    .unwrap_or_else(|| after_content(&lines, start, end));
+
+                let mut block = Vec::new();
+                if !lines[insert_at - 1].trim().is_empty() {
+                    block.push(String::new());
+                }
+                block.push(subheader);
+                block.push(String::new());
+                block.extend(entries);
+                if lines
+                    .get(insert_at)
+                    .map(|line| !line.trim().is_empty())
+                    .unwrap_or(false)
+                {
+                    block.push(String::new());
+                }
+                lines.splice(insert_at..insert_at, block);
+            }
+        }
+    }
+
+    let mut changelog_out = lines.join("\n");
+    if changelog.ends_with('\n') {
+        changelog_out.push('\n');
+    }
+    Ok(changelog_out)
+}
+
+fn is_unreleased_header(line: &str) -> bool {
+    line.strip_prefix("## ")
+        .map(|header| {
+            header
+                .trim_start_matches('[')
+                .to_ascii_lowercase()
+                .starts_with("unreleased")
+        })
+        .unwrap_or(false)
+}
+
+/// The line after the section starting at `start`
+fn section_end(lines: &[String], start: usize) -> usize {
+    lines[start + 1..]
+        .iter()
+        .position(|line| {
+            line.starts_with("## ") || line.starts_with("<!-- next-url -->") || is_link(line)
+        })
+        .map(|i| start + 1 + i)
+        .unwrap_or(lines.len())
+}
+
+/// Link reference definitions, like `[Unreleased]: https://...`
+fn is_link(line: &str) -> bool {
+    line.starts_with('[') && line.contains("]:")
+}
+
+/// The line after the last non-blank line within `start..end`
+fn after_content(lines: &[String], start: usize, end: usize) -> usize {
+    lines[start + 1..end]
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map(|i| start + 1 + i + 1)
+        .unwrap_or(start + 1)
+}
+
+fn section_order(heading: &str) -> usize {
+    SECTIONS
+        .iter()
+        .position(|section| *section == heading)
+        .unwrap_or(SECTIONS.len())
+}
+
+#[test]
+fn insert_into_empty_unreleased() {
+    let changelog = "# Change Log
+
+<!-- next-header -->
+## [Unreleased] - ReleaseDate
+
+## [0.1.0] - 2022-01-01
+
+### Added
+
+- Initial release
+
+<!-- next-url -->
+[Unreleased]: https://example.com
+";
+    let sections = [
+        ("Added", vec!["`foo::bar`: new".to_owned()]),
+        ("Removed", vec!["`foo::baz`: gone".to_owned()]),
+    ];
+    let expected = "# Change Log
+
+<!-- next-header -->
+## [Unreleased] - ReleaseDate
+
+### Added
+
+- `foo::bar`: new
+
+### Removed
+
+- `foo::baz`: gone
+
+## [0.1.0] - 2022-01-01
+
+### Added
+
+- Initial release
+
+<!-- next-url -->
+[Unreleased]: https://example.com
+";
+    let actual = insert_unreleased(changelog, &sections).unwrap();
+    assert_eq!(actual, expected);
+    // Re-running doesn't duplicate entries
+    let actual = insert_unreleased(&actual, &sections).unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn insert_into_existing_sections() {
+    let changelog = "## [Unreleased] - ReleaseDate
+
+### Added
+
+- Something by hand
+
+### Removed
+
+- Old thing
+";
+    let sections = [
+        ("Added", vec!["`foo::bar`: new".to_owned()]),
+        ("Changed", vec!["`foo::qux`: changed".to_owned()]),
+    ];
+    let expected = "## [Unreleased] - ReleaseDate
+
+### Added
+
+- Something by hand
+- `foo::bar`: new
+
+### Changed
+
+- `foo::qux`: changed
+
+### Removed
+
+- Old thing
+";
+    let actual = insert_unreleased(changelog, &sections).unwrap();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,37 @@
+/// Settings from `[workspace.metadata.crate-api]` and `[package.metadata.crate-api]`
+#[derive(Clone, Default, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(default)]
+pub struct Config {
+    /// Toolchain to generate rustdoc JSON with
+    pub toolchain: Option<String>,
+    /// Patterns for release tags, used to find the default base to diff against
+    pub tag_templates: Option<Vec<String>>,
+}
+
+impl Config {
+    pub fn from_metadata(
+        metadata: &cargo_metadata::Metadata,
+        pkg: &cargo_metadata::Package,
+    ) -> Result<Self, eyre::Report> {
+        let mut config = Self::from_value(&metadata.workspace_metadata)?;
+        config.update(Self::from_value(&pkg.metadata)?);
+        Ok(config)
+    }
+
+    fn from_value(metadata: &serde_json::Value) -> Result<Self, eyre::Report> {
+        match metadata.get("crate-api") {
+            Some(config) => Ok(serde_json::from_value(config.clone())?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn update(&mut self, source: Config) {
+        if let Some(toolchain) = source.toolchain {
+            self.toolchain = Some(toolchain);
+        }
+        if let Some(tag_templates) = source.tag_templates {
+            self.tag_templates = Some(tag_templates);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+/// Limit what is reported to the paths and diffs the user cares about
+#[derive(Clone, Debug, Default)]
+pub struct Filter {
+    paths: Option<globset::GlobSet>,
+    kinds: Vec<crate_api::PathKind>,
+    min_severity: Option<crate_api::diff::Severity>,
+}
+
+impl Filter {
+    pub fn new(
+        globs: &[String],
+        kinds: &[crate_api::PathKind],
+        min_severity: Option<crate_api::diff::Severity>,
+    ) -> Result<Self, globset::Error> {
+        let paths = if globs.is_empty() {
+            None
+        } else {
+            let mut builder = globset::GlobSetBuilder::new();
+            for glob in globs {
+                // Treat `::` like `/` so `*` stays within a module and `**` crosses them
+                let glob = globset::GlobBuilder::new(&glob.replace("::", "/"))
+                    .literal_separator(true)
+                    .build()?;
+                builder.add(glob);
+            }
+            Some(builder.build()?)
+        };
+        Ok(Self {
+            paths,
+            kinds: kinds.to_owned(),
+            min_severity,
+        })
+    }
+
+    /// Whether paths are being filtered
+    fn filters_paths(&self) -> bool {
+        self.paths.is_some() || !self.kinds.is_empty()
+    }
+
+    fn matches(&self, path: &crate_api::Path) -> bool {
+        let path_matches = self
+            .paths
+            .as_ref()
+            .map(|paths| paths.is_match(path.path.replace("::", "/")))
+            .unwrap_or(true);
+        let kind_matches = self.kinds.is_empty() || self.kinds.contains(&path.kind);
+        path_matches && kind_matches
+    }
+
+    pub fn apply_api(&self, api: &mut crate_api::Api) {
+        if self.filters_paths() {
+            api.retain_paths(|path| self.matches(path));
+        }
+    }
+
+    pub fn apply_diff(&self, report: &mut crate::report::Diff) {
+        let before = &report.before;
+        let after = &report.after;
+        report.diffs.retain(|diff| {
+            if let Some(min_severity) = self.min_severity {
+                if diff.severity < min_severity {
+                    return false;
+                }
+            }
+            if self.filters_paths() {
+                // Diffs not tied to a path, like dependency version changes, are filtered out
+                let path = diff
+                    .after
+                    .and_then(|location| location.path_id)
+                    .and_then(|path_id| after.paths.get(path_id))
+                    .or_else(|| {
+                        diff.before
+                            .and_then(|location| location.path_id)
+                            .and_then(|path_id| before.paths.get(path_id))
+                    });
+                return path.map(|path| self.matches(path)).unwrap_or(false);
+            }
+            true
+        });
+    }
+}
+
+#[cfg(test)]
+fn path(kind: crate_api::PathKind, path: &str) -> crate_api::Path {
+    crate_api::Path::new(kind, path)
+}
+
+#[test]
+fn globs_treat_modules_as_directories() {
+    let filter = Filter::new(
+        &["mycrate::a::*".to_owned(), "mycrate::b::**".to_owned()],
+        &[],
+        None,
+    )
+    .unwrap();
+    let matches = |name: &str| filter.matches(&path(crate_api::PathKind::Struct, name));
+
+    assert!(matches("mycrate::a::Abc"));
+    assert!(!matches("mycrate::a::c::Abc"));
+    assert!(matches("mycrate::b::Abc"));
+    assert!(matches("mycrate::b::c::d::Abc"));
+    assert!(!matches("mycrate::Abc"));
+    assert!(!matches("mycrate::ab::Abc"));
+}
+
+#[test]
+fn kinds_limit_paths() {
+    let filter = Filter::new(&[], &[crate_api::PathKind::Function], None).unwrap();
+
+    assert!(filter.matches(&path(crate_api::PathKind::Function, "mycrate::abc")));
+    assert!(!filter.matches(&path(crate_api::PathKind::Struct, "mycrate::Abc")));
+
+    let unfiltered = Filter::new(&[], &[], None).unwrap();
+    assert!(!unfiltered.filters_paths());
+    assert!(unfiltered.matches(&path(crate_api::PathKind::Struct, "mycrate::Abc")));
+}
@@ -1,14 +1,23 @@
+use std::collections::HashMap;
 use std::io::Write;
 
 use clap::Parser;
 use proc_exit::WithCodeResultExt;
 
 mod args;
+mod changelog;
+mod config;
+mod filter;
 mod log;
 mod report;
+mod rules;
 
 fn main() {
-    human_panic::setup_panic!();
+    // `human-panic` 1.x names `PanicInfo`, deprecated since Rust 1.81, and 2.x is past our MSRV
+    #[allow(deprecated)]
+    {
+        human_panic::setup_panic!();
+    }
     let result = run();
     proc_exit::exit(result);
 }
@@ -31,7 +40,45 @@ fn run() -> proc_exit::ExitResult {
 
     log::init_logging(args.verbose.clone(), colored_stderr);
 
-    let mut success = true;
+    if args.list_rules {
+        rules::render_rules(&mut std::io::stdout());
+        return proc_exit::Code::SUCCESS.ok();
+    }
+    if let Some(name) = args.explain.as_deref() {
+        let id = rules::find(name).ok_or_else(|| {
+            proc_exit::Code::USAGE_ERR
+                .with_message(format!("unknown rule `{}`, see `--list-rules`", name))
+        })?;
+        rules::render_explanation(&mut std::io::stdout(), id);
+        return proc_exit::Code::SUCCESS.ok();
+    }
+
+    let mode = args.mode();
+    if !args.format.supports(mode) {
+        return Err(proc_exit::Code::USAGE_ERR.with_message(format!(
+            "`--format {}` is not supported in this mode",
+            clap::ArgEnum::to_possible_value(&args.format)
+                .expect("no formats are skipped")
+                .get_name()
+        )));
+    }
+
+    let filter = args.to_filter().with_code(proc_exit::Code::USAGE_ERR)?;
+
+    if let (Some(before), Some(after)) = (args.before.as_deref(), args.after.as_deref()) {
+        return match diff_files(
+            before,
+            after,
+            args.changelog.as_deref(),
+            &filter,
+            args.format,
+        ) {
+            Ok(()) => proc_exit::Code::SUCCESS.ok(),
+            Err(err) => report_error(&err).ok(),
+        };
+    }
+
+    let mut code = proc_exit::Code::SUCCESS;
 
     let metadata = args
         .manifest
@@ -39,103 +86,486 @@ fn run() -> proc_exit::ExitResult {
         .exec()
         .with_code(proc_exit::Code::CONFIG_ERR)?;
 
-    let mode = args.mode();
     let base = match mode {
         args::Mode::DumpRaw => None,
         args::Mode::Api => None,
-        args::Mode::Diff => {
-            let base = args
-                .base()
-                .map(Ok)
-                .unwrap_or_else(|| find_default_base(metadata.workspace_root.as_std_path()))
-                .with_code(proc_exit::Code::FAILURE)?;
-            Some(base)
-        }
+        args::Mode::Diff => args.base(),
     };
 
     let (selected, _) = args.workspace.partition_packages(&metadata);
-    for selected in selected {
-        let res = match mode {
-            args::Mode::DumpRaw => dump_raw(selected, args.format),
-            args::Mode::Api => api(selected, args.format),
-            args::Mode::Diff => diff(&metadata, selected, base.clone().unwrap(), args.format),
-        };
-        match res {
-            Ok(()) => {}
+    let selected: Vec<_> = selected.into_iter().cloned().collect();
+    let names: Vec<_> = selected.iter().map(|pkg| pkg.name.clone()).collect();
+    let workspace = args.selects_workspace(&metadata);
+    let job = Job {
+        mode,
+        base,
+        head: args.head.clone(),
+        worktrees: Default::default(),
+        cache: !args.no_cache,
+        config: args.to_config(),
+        filter,
+        label: 1 < args.jobs && 1 < selected.len(),
+    };
+    let results = run_jobs(std::sync::Arc::new(metadata), selected, job, args.jobs);
+    let mut outputs = Vec::new();
+    let mut failed = Vec::new();
+    for (name, result) in names.iter().zip(results) {
+        match result {
+            Ok(output) => outputs.push((name.as_str(), output)),
             Err(err) => {
-                ::log::error!("{}", err);
-                success = false;
-                continue;
+                let err_code = report_error(&err);
+                failed.push((name.as_str(), err.to_string()));
+                // Exit with the first package's failure
+                if code.is_ok() {
+                    code = err_code;
+                }
             }
+        }
+    }
+
+    if let (Some(changelog), false) = (args.changelog.as_deref(), outputs.is_empty()) {
+        // Written once, after every package, so entries don't race or interleave
+        let reports: Vec<_> = outputs
+            .iter()
+            .filter_map(|(name, output)| match output {
+                Output::Diff(report) => Some((*name, report)),
+                _ => None,
+            })
+            .collect();
+        let sections = if 1 < names.len() {
+            report::workspace_changelog_sections(&reports)
+        } else {
+            reports
+                .first()
+                .map(|(_, report)| report::changelog_sections(report))
+                .unwrap_or_default()
         };
+        if let Err(err) = update_changelog(changelog, &sections) {
+            let err_code = report_error(&err);
+            if code.is_ok() {
+                code = err_code;
+            }
+        }
     }
 
-    if success {
-        proc_exit::Code::SUCCESS.ok()
-    } else {
-        proc_exit::Code::FAILURE.ok()
+    let stdout = std::io::stdout();
+    if let Err(err) = write_outputs(
+        &mut stdout.lock(),
+        &outputs,
+        &failed,
+        workspace,
+        mode,
+        args.format,
+    ) {
+        let err_code = report_error(&err);
+        if code.is_ok() {
+            code = err_code;
+        }
     }
+
+    code.ok()
 }
 
-fn dump_raw(pkg: &cargo_metadata::Package, format: args::Format) -> Result<(), eyre::Report> {
-    let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).color();
-    let raw = crate_api::RustDocBuilder::new()
-        .color(colored_stderr)
+/// What was extracted for a package, rendered once every package is done
+enum Output {
+    Raw(report::Raw),
+    Api(report::Api),
+    Diff(report::Diff),
+}
+
+impl Output {
+    fn write(&self, stdout: &mut dyn Write, format: args::Format) -> Result<(), eyre::Report> {
+        match self {
+            Self::Raw(raw) => write_raw(stdout, raw, format),
+            Self::Api(api) => write_api(stdout, api, format),
+            Self::Diff(report) => write_diff(stdout, report, format),
+        }
+    }
+
+    /// The same value `--format json` writes for a single package
+    fn to_json(&self) -> Result<serde_json::Value, serde_json::Error> {
+        match self {
+            Self::Raw(raw) => serde_json::to_value(raw),
+            Self::Api(api) => serde_json::to_value(&api.api),
+            Self::Diff(report) => serde_json::to_value(report),
+        }
+    }
+
+    fn summary(&self) -> String {
+        match self {
+            Self::Raw(raw) => report::raw_summary(raw),
+            Self::Api(api) => report::api_summary(&api.api),
+            Self::Diff(report) => report::diff_summary(report),
+        }
+    }
+}
+
+/// Write every package's output, combining them into one document for a `workspace`
+fn write_outputs(
+    stdout: &mut dyn Write,
+    outputs: &[(&str, Output)],
+    failed: &[(&str, String)],
+    workspace: bool,
+    mode: args::Mode,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    match format {
+        args::Format::Json if workspace => {
+            let mut packages = serde_json::Map::new();
+            for (name, output) in outputs {
+                packages.insert((*name).to_owned(), output.to_json()?);
+            }
+            for (name, message) in failed {
+                packages.insert((*name).to_owned(), serde_json::json!({ "error": message }));
+            }
+            let _ = writeln!(stdout, "{}", serde_json::to_string(&packages)?);
+        }
+        args::Format::Md if workspace => {
+            let mut sections = Vec::new();
+            for (name, output) in outputs {
+                let mut markdown = Vec::new();
+                output.write(&mut markdown, format)?;
+                sections.push(report::Section {
+                    name,
+                    summary: output.summary(),
+                    body: markdown,
+                });
+            }
+            report::render_workspace_markdown(stdout, &sections, failed.len());
+        }
+        args::Format::Html if workspace => {
+            let mut sections = Vec::new();
+            for (name, output) in outputs {
+                let mut html = Vec::new();
+                match output {
+                    Output::Api(api) => report::render_api_html_body(&mut html, api, 3),
+                    Output::Diff(report) => report::render_diff_html_body(&mut html, report, 3),
+                    Output::Raw(_) => unreachable!("unsupported formats are rejected by `run`"),
+                }
+                sections.push(report::Section {
+                    name,
+                    summary: output.summary(),
+                    body: html,
+                });
+            }
+            report::render_workspace_html(stdout, &sections, failed.len());
+        }
+        args::Format::Sarif => {
+            let reports: Vec<_> = outputs
+                .iter()
+                .filter_map(|(_, output)| match output {
+                    Output::Diff(report) => Some(report),
+                    _ => None,
+                })
+                .collect();
+            report::render_diff_sarif(stdout, &reports)?;
+        }
+        args::Format::Changelog if workspace => {
+            let reports: Vec<_> = outputs
+                .iter()
+                .filter_map(|(name, output)| match output {
+                    Output::Diff(report) => Some((*name, report)),
+                    _ => None,
+                })
+                .collect();
+            let sections = report::workspace_changelog_sections(&reports);
+            report::render_changelog_sections(stdout, &sections);
+        }
+        args::Format::Junit => {
+            report::render_junit_header(stdout);
+            for (_, output) in outputs {
+                output.write(stdout, format)?;
+            }
+            for (name, message) in failed {
+                report::render_error_junit(stdout, name, message);
+            }
+            report::render_junit_footer(stdout);
+        }
+        _ => {
+            for (_, output) in outputs {
+                output.write(stdout, format)?;
+            }
+            if workspace && format == args::Format::Pretty {
+                let _ = writeln!(stdout, "{}", workspace_summary(outputs, failed.len(), mode));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn workspace_summary(outputs: &[(&str, Output)], failed: usize, mode: args::Mode) -> String {
+    let packages = report::plural(outputs.len(), "package", "packages");
+    let mut summary = match mode {
+        args::Mode::DumpRaw => format!("Dumped rustdoc JSON for {}", packages),
+        args::Mode::Api => format!("Extracted the API of {}", packages),
+        args::Mode::Diff => {
+            let breaking = outputs
+                .iter()
+                .filter(|(_, output)| match output {
+                    Output::Diff(report) => report::is_breaking(report),
+                    _ => false,
+                })
+                .count();
+            format!("Compared {}, {} with breaking changes", packages, breaking)
+        }
+    };
+    if 0 < failed {
+        summary.push_str(&format!(", {} failed", failed));
+    }
+    summary
+}
+
+/// Log `err` with any advice for fixing it, returning the exit code for it
+fn report_error(err: &eyre::Report) -> proc_exit::Code {
+    ::log::error!("{}", err);
+
+    let kind = match err.downcast_ref::<crate_api::Error>() {
+        Some(err) => err.kind(),
+        None => return proc_exit::Code::FAILURE,
+    };
+    let (code, help) = match kind {
+        crate_api::ErrorKind::Toolchain => (proc_exit::Code::SERVICE_UNAVAILABLE, None),
+        crate_api::ErrorKind::UnsupportedFormatVersion => (
+            proc_exit::Code::PROTOCOL_ERR,
+            Some("select a nightly that emits a supported format with `--toolchain`"),
+        ),
+        crate_api::ErrorKind::InvalidManifest => (proc_exit::Code::CONFIG_ERR, None),
+        crate_api::ErrorKind::VirtualManifest => (
+            proc_exit::Code::CONFIG_ERR,
+            Some("select packages with `--package` or `--workspace`"),
+        ),
+        crate_api::ErrorKind::CompileError => (
+            proc_exit::Code::DATA_ERR,
+            Some("the package must build with `cargo doc --all-features`"),
+        ),
+        crate_api::ErrorKind::MissingOutput => (
+            proc_exit::Code::NO_INPUT,
+            Some("check that the toolchain supports `--output-format=json`"),
+        ),
+        crate_api::ErrorKind::Git => (proc_exit::Code::IO_ERR, None),
+        crate_api::ErrorKind::Registry => (
+            proc_exit::Code::SERVICE_UNAVAILABLE,
+            Some("pass `--git` or `--path` to diff an unpublished package against"),
+        ),
+        crate_api::ErrorKind::ApiParse => (proc_exit::Code::SOFTWARE_ERR, None),
+        _ => (proc_exit::Code::FAILURE, None),
+    };
+    if let Some(help) = help {
+        ::log::info!("help: {}", help);
+    }
+    code
+}
+
+#[derive(Clone, Debug)]
+struct Job {
+    mode: args::Mode,
+    /// Diff against each package's latest release when unset
+    base: Option<report::Source>,
+    /// Diff this revision rather than the working tree
+    head: Option<String>,
+    /// Git revisions that have been checked out, shared between packages
+    worktrees: std::sync::Arc<std::sync::Mutex<HashMap<String, crate_api::Worktree>>>,
+    cache: bool,
+    config: config::Config,
+    filter: filter::Filter,
+    /// Whether packages are processed concurrently, requiring their progress to be told apart
+    label: bool,
+}
+
+impl Job {
+    fn run(
+        &self,
+        metadata: &cargo_metadata::Metadata,
+        pkg: &cargo_metadata::Package,
+    ) -> Result<Output, eyre::Report> {
+        let mut config = config::Config::from_metadata(metadata, pkg)?;
+        config.update(self.config.clone());
+        let label = self.label.then(|| pkg.name.clone());
+
+        let output = match self.mode {
+            args::Mode::DumpRaw => Output::Raw(dump_raw(pkg, &config, label)?),
+            args::Mode::Api => Output::Api(api(metadata, pkg, &config, label, &self.filter)?),
+            args::Mode::Diff => {
+                let base = match self.base.clone() {
+                    Some(base) => base,
+                    None => find_default_base(pkg, &config)?,
+                };
+                Output::Diff(diff(metadata, pkg, &config, base, self)?)
+            }
+        };
+        Ok(output)
+    }
+
+    fn worktree(
+        &self,
+        metadata: &cargo_metadata::Metadata,
+        rev: &str,
+    ) -> Result<crate_api::Worktree, crate_api::Error> {
+        // Hold the lock while checking out so concurrent packages don't race on the repo
+        let mut worktrees = self.worktrees.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(worktree) = worktrees.get(rev) {
+            return Ok(worktree.clone());
+        }
+        let worktree = crate_api::Worktree::checkout(
+            metadata.workspace_root.as_std_path(),
+            rev,
+            metadata.target_directory.join("crate-api").as_std_path(),
+        )?;
+        worktrees.insert(rev.to_owned(), worktree.clone());
+        Ok(worktree)
+    }
+}
+
+/// Run `job` on up to `jobs` packages at a time, returning each package's output in order
+fn run_jobs(
+    metadata: std::sync::Arc<cargo_metadata::Metadata>,
+    packages: Vec<cargo_metadata::Package>,
+    job: Job,
+    jobs: usize,
+) -> Vec<Result<Output, eyre::Report>> {
+    let count = packages.len();
+    let queue = std::sync::Arc::new(std::sync::Mutex::new(
+        packages
+            .into_iter()
+            .enumerate()
+            .collect::<std::collections::VecDeque<_>>(),
+    ));
+    let job = std::sync::Arc::new(job);
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let workers: Vec<_> = (0..jobs.clamp(1, count.max(1)))
+        .map(|_| {
+            let metadata = metadata.clone();
+            let queue = queue.clone();
+            let job = job.clone();
+            let tx = tx.clone();
+            std::thread::spawn(move || loop {
+                let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                let (i, pkg) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                let result = job.run(&metadata, &pkg);
+                let _ = tx.send((i, result));
+            })
+        })
+        .collect();
+    drop(tx);
+
+    let mut results: Vec<_> = rx.into_iter().collect();
+    for worker in workers {
+        if let Err(panic) = worker.join() {
+            std::panic::resume_unwind(panic);
+        }
+    }
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn dump_raw(
+    pkg: &cargo_metadata::Package,
+    config: &config::Config,
+    label: Option<String>,
+) -> Result<report::Raw, eyre::Report> {
+    let raw = rustdoc(config)
+        .label(label)
         .dump_raw(pkg.manifest_path.as_path().as_std_path())?;
-    let raw: rustdoc_json_types_fork::Crate = serde_json::from_str(&raw)?;
+    let raw = crate_api::rustdoc::parse_raw_crate(&raw, pkg.manifest_path.as_path().as_std_path())?;
 
     let manifest = crate_api::manifest::Manifest::from(pkg);
 
-    let raw = report::Raw {
+    Ok(report::Raw {
         manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
         rustdoc: Some(raw),
         manifest: Some(manifest),
-    };
+    })
+}
 
+fn write_raw(
+    stdout: &mut dyn Write,
+    raw: &report::Raw,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
     match format {
         args::Format::Silent => {}
         args::Format::Pretty => {
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string_pretty(&raw)?);
+            let _ = writeln!(stdout, "{}", serde_json::to_string_pretty(raw)?);
         }
         args::Format::Md => {
             let _ = writeln!(
-                std::io::stdout(),
+                stdout,
                 "```json
 {}
 ```",
-                serde_json::to_string_pretty(&raw)?
+                serde_json::to_string_pretty(raw)?
             );
         }
         args::Format::Json => {
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string(&raw)?);
+            let _ = writeln!(stdout, "{}", serde_json::to_string(raw)?);
+        }
+        args::Format::Html
+        | args::Format::Sarif
+        | args::Format::Github
+        | args::Format::Changelog
+        | args::Format::Junit
+        | args::Format::Text => {
+            unreachable!("unsupported formats are rejected by `run`")
         }
     }
 
     Ok(())
 }
 
-fn api(pkg: &cargo_metadata::Package, format: args::Format) -> Result<(), eyre::Report> {
-    let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).color();
-    let mut api = crate_api::RustDocBuilder::new()
-        .color(colored_stderr)
+fn api(
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    config: &config::Config,
+    label: Option<String>,
+    filter: &filter::Filter,
+) -> Result<report::Api, eyre::Report> {
+    let mut api = rustdoc(config)
+        .label(label)
         .into_api(pkg.manifest_path.as_path().as_std_path())?;
 
-    let manifest = crate_api::manifest::Manifest::from(pkg);
+    let manifest = crate_api::manifest::Manifest::from_metadata(metadata, pkg);
     manifest.into_api(&mut api);
+    filter.apply_api(&mut api);
+
+    Ok(report::Api {
+        manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+        workspace_root: Some(metadata.workspace_root.clone().into_std_path_buf()),
+        api,
+    })
+}
 
+fn write_api(
+    stdout: &mut dyn Write,
+    report: &report::Api,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
     match format {
         args::Format::Silent => {}
         args::Format::Pretty => {
             // HACK: Real version (using `termtree`) isn't implemented yet
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string_pretty(&api)?);
+            let _ = writeln!(stdout, "{}", serde_json::to_string_pretty(&report.api)?);
         }
         args::Format::Md => {
-            let stdout = std::io::stdout();
-            let mut stdout = stdout.lock();
-            report::render_api_markdown(&mut stdout, &api)?;
+            report::render_api_markdown(stdout, &report.api)?;
+        }
+        args::Format::Html => {
+            report::render_api_html(stdout, report)?;
         }
         args::Format::Json => {
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string(&api)?);
+            let _ = writeln!(stdout, "{}", serde_json::to_string(&report.api)?);
+        }
+        args::Format::Text => {
+            report::render_api_text(stdout, &report.api)?;
+        }
+        args::Format::Sarif
+        | args::Format::Github
+        | args::Format::Changelog
+        | args::Format::Junit => {
+            unreachable!("unsupported formats are rejected by `run`")
         }
     }
 
@@ -145,160 +575,334 @@ fn api(pkg: &cargo_metadata::Package, format: args::Format) -> Result<(), eyre::
 fn diff(
     metadata: &cargo_metadata::Metadata,
     pkg: &cargo_metadata::Package,
+    config: &config::Config,
     base: report::Source,
-    format: args::Format,
+    job: &Job,
+) -> Result<report::Diff, eyre::Report> {
+    let builder = crate_api::ComparisonBuilder::new()
+        .rustdoc(rustdoc(config))
+        .cache(job.cache)
+        .target_directory(metadata.target_directory.join("crate-api"));
+    let mut head_commit = None;
+    let head_manifest_path;
+    let manifest_path = match job.head.as_deref() {
+        Some(head) => {
+            let worktree = job.worktree(metadata, head)?;
+            head_commit = Some(worktree.commit_id().to_owned());
+            head_manifest_path = worktree.manifest_path(&pkg.name)?;
+            head_manifest_path.as_path()
+        }
+        None => pkg.manifest_path.as_std_path(),
+    };
+    let mut commit = None;
+    let mut against = base.clone();
+    let comparison = match &base {
+        report::Source::Git(rev) => {
+            let worktree = job.worktree(metadata, rev)?;
+            ::log::info!(
+                "Comparing {} against {} ({})",
+                pkg.name,
+                base,
+                worktree.commit_id()
+            );
+            commit = Some(worktree.commit_id().to_owned());
+            builder.compare_worktree(&worktree, manifest_path)?
+        }
+        report::Source::Path(path) => builder.compare_paths(path, manifest_path)?,
+        report::Source::Registry(spec) => {
+            let (name, version_req) = registry_spec(spec, pkg)?;
+            let package = crate_api::RegistryPackage::download(
+                name,
+                &version_req,
+                &metadata
+                    .target_directory
+                    .join("crate-api")
+                    .into_std_path_buf(),
+            )?;
+            ::log::info!(
+                "Comparing {} against {} {} from the registry",
+                pkg.name,
+                package.name(),
+                package.version()
+            );
+            against = report::Source::Registry(format!("{}@{}", package.name(), package.version()));
+            builder.compare_paths(&package.manifest_path(), manifest_path)?
+        }
+        report::Source::File(_) => unreachable!("`--before` is diffed by `diff_files`"),
+    };
+
+    let mut report = report::Diff {
+        manifest_path: Some(manifest_path.to_owned()),
+        workspace_root: Some(metadata.workspace_root.clone().into_std_path_buf()),
+        against,
+        commit,
+        head: job.head.clone(),
+        head_commit,
+        before: comparison.before,
+        after: comparison.after,
+        diffs: comparison.diffs,
+    };
+    job.filter.apply_diff(&mut report);
+    Ok(report)
+}
+
+fn update_changelog(
+    path: &std::path::Path,
+    sections: &[(&str, Vec<String>)],
 ) -> Result<(), eyre::Report> {
-    let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).color();
-    let mut after = crate_api::RustDocBuilder::new()
-        .color(colored_stderr)
-        .into_api(pkg.manifest_path.as_path().as_std_path())?;
-    let manifest = crate_api::manifest::Manifest::from(pkg);
-    manifest.into_api(&mut after);
+    let changelog = std::fs::read_to_string(path)
+        .map_err(|e| eyre::eyre!("Failed when reading {}: {}", path.display(), e))?;
+    let changelog = changelog::insert_unreleased(&changelog, sections)
+        .map_err(|e| eyre::eyre!("Failed to update {}: {}", path.display(), e))?;
+    std::fs::write(path, changelog)
+        .map_err(|e| eyre::eyre!("Failed when writing {}: {}", path.display(), e))?;
+    Ok(())
+}
 
-    let base_path = resolve_source_path(metadata, pkg, &base)?;
-    let mut before = crate_api::RustDocBuilder::new()
-        .color(colored_stderr)
-        .into_api(&base_path)?;
-    let old_pkg = resolve_package(&base_path)?;
-    let manifest = crate_api::manifest::Manifest::from(&old_pkg);
-    manifest.into_api(&mut before);
+/// Diff APIs that were extracted ahead of time, without building anything
+fn diff_files(
+    before: &std::path::Path,
+    after: &std::path::Path,
+    changelog: Option<&std::path::Path>,
+    filter: &filter::Filter,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let comparison = crate_api::Comparison::new(load_api(before)?, load_api(after)?);
+    let mut report = report::Diff {
+        manifest_path: None,
+        workspace_root: None,
+        against: report::Source::File(before.to_owned()),
+        commit: None,
+        head: None,
+        head_commit: None,
+        before: comparison.before,
+        after: comparison.after,
+        diffs: comparison.diffs,
+    };
+    filter.apply_diff(&mut report);
+    if let Some(changelog) = changelog {
+        update_changelog(changelog, &report::changelog_sections(&report))?;
+    }
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
+    if format == args::Format::Junit {
+        report::render_junit_header(&mut stdout);
+    }
+    write_diff(&mut stdout, &report, format)?;
+    if format == args::Format::Junit {
+        report::render_junit_footer(&mut stdout);
+    }
+    Ok(())
+}
 
-    let mut diffs = Vec::new();
-    crate_api::diff::diff(&before, &after, &mut diffs);
+/// Load the output of `--api`, `--dump-raw`, or rustdoc
+fn load_api(path: &std::path::Path) -> Result<crate_api::Api, eyre::Report> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| eyre::eyre!("Failed when reading {}: {}", path.display(), e))?;
+    let value: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| eyre::eyre!("Failed when parsing {}: {}", path.display(), e))?;
+    if value.get("format_version").is_some() {
+        Ok(crate_api::rustdoc::parse_raw(&raw, path)?)
+    } else if let Some(rustdoc) = value.get("rustdoc") {
+        let mut api = crate_api::rustdoc::parse_raw(&rustdoc.to_string(), path)?;
+        if let Some(manifest) = value.get("manifest").filter(|m| !m.is_null()) {
+            let manifest: crate_api::manifest::Manifest = serde_json::from_value(manifest.clone())
+                .map_err(|e| eyre::eyre!("Failed when parsing {}: {}", path.display(), e))?;
+            manifest.into_api(&mut api);
+        }
+        Ok(api)
+    } else {
+        serde_json::from_value(value)
+            .map_err(|e| eyre::eyre!("Failed when parsing {}: {}", path.display(), e))
+    }
+}
 
+fn write_diff(
+    stdout: &mut dyn Write,
+    report: &report::Diff,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
     match format {
         args::Format::Silent => {}
         args::Format::Pretty => {
             // HACK: Real version (using `termtree`) isn't implemented yet
-            let raw = report::Diff {
-                manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
-                against: base,
-                before,
-                after,
-                diffs,
-            };
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string_pretty(&raw)?);
+            let _ = writeln!(stdout, "{}", serde_json::to_string_pretty(report)?);
         }
         args::Format::Md => {
-            let stdout = std::io::stdout();
-            let mut stdout = stdout.lock();
-            report::render_diff_markdown(&mut stdout, &before, &after, &diffs)?;
+            report::render_diff_markdown(stdout, report)?;
+        }
+        args::Format::Html => {
+            report::render_diff_html(stdout, report)?;
+        }
+        args::Format::Sarif => {
+            report::render_diff_sarif(stdout, &[report])?;
+        }
+        args::Format::Changelog => {
+            report::render_diff_changelog(stdout, report)?;
+        }
+        args::Format::Junit => {
+            report::render_diff_junit(stdout, report)?;
+        }
+        args::Format::Github => {
+            report::render_diff_github(stdout, report)?;
+            if let Some(summary_path) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+                let mut summary = Vec::new();
+                report::render_diff_markdown(&mut summary, report)?;
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&summary_path)?;
+                file.write_all(&summary)?;
+            }
         }
         args::Format::Json => {
-            let raw = report::Diff {
-                manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
-                against: base,
-                before,
-                after,
-                diffs,
-            };
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string(&raw)?);
+            let _ = writeln!(stdout, "{}", serde_json::to_string(report)?);
+        }
+        args::Format::Text => {
+            unreachable!("unsupported formats are rejected by `run`")
         }
     }
 
     Ok(())
 }
 
-fn find_default_base(path: &std::path::Path) -> Result<report::Source, eyre::Report> {
-    let repo = git2::Repository::discover(path)?;
+fn rustdoc(config: &config::Config) -> crate_api::RustDocBuilder {
+    let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).color();
+    crate_api::RustDocBuilder::new()
+        .color(colored_stderr)
+        .toolchain(config.toolchain.clone())
+}
+
+const DEFAULT_TAG_TEMPLATES: &[&str] = &["{{crate_name}}-v{{version}}", "v{{version}}"];
 
-    let mut tags = std::collections::HashMap::new();
-    repo.tag_foreach(|oid, name| {
-        if let Ok(name) = std::str::from_utf8(name) {
-            tags.insert(oid, name.to_owned());
+/// Find the latest release of `pkg` to diff against
+///
+/// This is the highest tagged version reachable from `HEAD` that isn't newer than `pkg`, falling
+/// back to the highest such version published to the registry.
+fn find_default_base(
+    pkg: &cargo_metadata::Package,
+    config: &config::Config,
+) -> Result<report::Source, eyre::Report> {
+    let templates = config.tag_templates.clone().unwrap_or_else(|| {
+        DEFAULT_TAG_TEMPLATES
+            .iter()
+            .map(|t| (*t).to_owned())
+            .collect()
+    });
+    let repo = git2::Repository::discover(pkg.manifest_path.as_std_path());
+    let head = repo
+        .as_ref()
+        .ok()
+        .and_then(|repo| Some(repo.head().ok()?.peel_to_commit().ok()?.id()));
+    let (repo, head) = match (repo, head) {
+        (Ok(repo), Some(head)) => (repo, head),
+        _ => {
+            ::log::debug!(
+                "No git history to find a release of {} in, falling back to the registry",
+                pkg.name
+            );
+            return Ok(report::Source::Registry(pkg.name.clone()));
         }
-        true
-    })?;
+    };
+
+    let mut candidates = Vec::new();
+    for tag in repo.tag_names(None)?.iter().flatten() {
+        let version = match templates
+            .iter()
+            .find_map(|template| match_tag(template, &pkg.name, tag))
+        {
+            Some(version) => version,
+            None => continue,
+        };
+        if pkg.version < version {
+            continue;
+        }
+        let commit = match repo
+            .revparse_single(&format!("refs/tags/{}", tag))
+            .and_then(|object| object.peel_to_commit())
+        {
+            Ok(commit) => commit.id(),
+            Err(_) => continue,
+        };
+        candidates.push((version, tag.to_owned(), commit));
+    }
+    candidates.sort();
 
-    let mut revwalk = repo.revwalk()?;
-    revwalk.push_head()?;
-    for oid in revwalk {
-        let oid = oid?;
-        if let Some(tag) = tags.remove(&oid) {
+    for (_, tag, commit) in candidates.into_iter().rev() {
+        if commit == head || repo.graph_descendant_of(head, commit)? {
+            ::log::debug!("Selected {} ({}) as the base for {}", tag, commit, pkg.name);
             return Ok(report::Source::Git(tag));
         }
     }
 
-    eyre::bail!("Could not find a tag for {} for base", path.display());
+    ::log::debug!(
+        "No release tag of {} {} or earlier is reachable from HEAD (looked for {}), falling back to the registry",
+        pkg.name,
+        pkg.version,
+        templates
+            .iter()
+            .map(|t| format!("`{}`", t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(report::Source::Registry(pkg.name.clone()))
 }
 
-fn resolve_package(path: &std::path::Path) -> Result<cargo_metadata::Package, eyre::Report> {
-    let metadata = cargo_metadata::MetadataCommand::new()
-        .manifest_path(path)
-        .exec()?;
-    let root_id = metadata
-        .resolve
-        .expect("present because called with deps")
-        .root
-        .ok_or_else(|| {
-            eyre::eyre!(
-                "Expected package manifest, got virtual workspace at {}",
-                path.display()
-            )
-        })?;
-    let pkg = metadata
-        .packages
-        .into_iter()
-        .find(|p| p.id == root_id)
-        .expect("resolved root_id to exist");
-    Ok(pkg)
+/// The version in `tag`, if it matches `template`
+fn match_tag(template: &str, crate_name: &str, tag: &str) -> Option<cargo_metadata::Version> {
+    let template = template.replace("{{crate_name}}", crate_name);
+    let (prefix, suffix) = template.split_once("{{version}}")?;
+    let version = tag.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    cargo_metadata::Version::parse(version).ok()
 }
 
-fn resolve_source_path(
-    metadata: &cargo_metadata::Metadata,
+/// The package and version requirement to download for `--registry <spec>`
+///
+/// `spec` is `<name>@<version>` for that exact version or `<name>` for the highest version that
+/// isn't newer than `pkg`.
+fn registry_spec<'s>(
+    spec: &'s str,
     pkg: &cargo_metadata::Package,
-    source: &report::Source,
-) -> Result<std::path::PathBuf, eyre::Report> {
-    match source {
-        report::Source::Git(rev) => {
-            let target = metadata
-                .target_directory
-                .join(format!("crate-api/{}-base", pkg.name))
-                .into_std_path_buf();
-            checkout_ref(pkg.manifest_path.as_std_path(), &target, rev)?;
-            find_by_package_name(&pkg.name, &target)
-        }
-        report::Source::Path(path) => Ok(path.to_owned()),
-        report::Source::Registry(_) => {
-            todo!()
-        }
-    }
-}
-
-fn find_by_package_name(
-    name: &str,
-    target: &std::path::Path,
-) -> Result<std::path::PathBuf, eyre::Report> {
-    let metadata = cargo_metadata::MetadataCommand::new()
-        .current_dir(target)
-        .no_deps()
-        .exec()?;
-    metadata
-        .packages
-        .iter()
-        .filter(|p| metadata.workspace_members.contains(&p.id))
-        .filter(|p| p.name == name)
-        .map(|p| p.manifest_path.as_std_path().to_owned())
-        .next()
-        .ok_or_else(|| eyre::eyre!("Could no find {} at {}", name, target.display()))
+) -> Result<(&'s str, cargo_metadata::VersionReq), eyre::Report> {
+    let (name, version_req) = match spec.split_once('@') {
+        Some((name, version)) => (name, format!("={}", version)),
+        None => (spec, format!("<={}", pkg.version)),
+    };
+    let version_req = cargo_metadata::VersionReq::parse(&version_req)
+        .map_err(|e| eyre::eyre!("Invalid version in `--registry {}`: {}", spec, e))?;
+    Ok((name, version_req))
 }
 
-fn checkout_ref(
-    source: &std::path::Path,
-    target: &std::path::Path,
-    rev: &str,
-) -> Result<(), eyre::Report> {
-    let repo = git2::Repository::discover(source)?;
-
-    let rev = repo.revparse_single(rev)?;
-
-    let mut co = git2::build::CheckoutBuilder::new();
-    co.target_dir(target)
-        .remove_untracked(true)
-        .remove_ignored(true)
-        .use_ours(true)
-        .force();
-    repo.checkout_tree(&rev, Some(&mut co))?;
-
-    Ok(())
+#[test]
+fn match_tag_templates() {
+    let version = |v: &str| Some(cargo_metadata::Version::parse(v).unwrap());
+    let cases = [
+        ("v{{version}}", "v1.2.3", version("1.2.3")),
+        ("v{{version}}", "v1.2.3-alpha.1", version("1.2.3-alpha.1")),
+        ("v{{version}}", "1.2.3", None),
+        ("v{{version}}", "v1.2", None),
+        (
+            "{{crate_name}}-v{{version}}",
+            "foo-v0.1.0",
+            version("0.1.0"),
+        ),
+        ("{{crate_name}}-v{{version}}", "foo-bar-v0.1.0", None),
+        ("{{crate_name}}-v{{version}}", "v0.1.0", None),
+        (
+            "{{crate_name}}@{{version}}-final",
+            "foo@2.0.0-final",
+            version("2.0.0"),
+        ),
+        ("{{crate_name}}@{{version}}-final", "foo@2.0.0", None),
+        ("release", "release", None),
+    ];
+    for (template, tag, expected) in cases {
+        assert_eq!(
+            match_tag(template, "foo", tag),
+            expected,
+            "{} {}",
+            template,
+            tag
+        );
+    }
 }
@@ -1,3 +1,7 @@
+mod html;
+
+pub use html::*;
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Raw {
@@ -10,14 +14,30 @@ pub struct Raw {
 #[serde(rename_all = "snake_case")]
 pub struct Api {
     pub manifest_path: std::path::PathBuf,
+    /// What rustdoc's spans are relative to
+    #[serde(skip)]
+    pub workspace_root: Option<std::path::PathBuf>,
     pub api: crate_api::Api,
 }
 
 #[derive(serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Diff {
-    pub manifest_path: std::path::PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_path: Option<std::path::PathBuf>,
+    /// What rustdoc's spans are relative to, when known
+    #[serde(skip)]
+    pub workspace_root: Option<std::path::PathBuf>,
     pub against: Source,
+    /// The commit `against` resolved to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// The revision diffed, when not the working tree
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// The commit `head` resolved to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head_commit: Option<String>,
     pub before: crate_api::Api,
     pub after: crate_api::Api,
     pub diffs: Vec<crate_api::diff::Diff>,
@@ -29,6 +49,19 @@ pub enum Source {
     Git(String),
     Path(std::path::PathBuf),
     Registry(String),
+    /// A previously extracted API
+    File(std::path::PathBuf),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Git(rev) => write!(f, "`{}`", rev),
+            Self::Path(path) => write!(f, "`{}`", path.display()),
+            Self::Registry(name) => write!(f, "`{}` from the registry", name),
+            Self::File(path) => write!(f, "`{}`", path.display()),
+        }
+    }
 }
 
 pub fn render_api_markdown(
@@ -86,6 +119,13 @@ pub fn render_api_markdown(
                 let _ = writeln!(writer, "**`{}`** *({:?})*", next_path.path, next_path.kind);
                 let _ = writeln!(writer);
 
+                if let Some(signature) = next_path.signature.as_deref() {
+                    let _ = writeln!(writer, "```rust");
+                    let _ = writeln!(writer, "{}", signature);
+                    let _ = writeln!(writer, "```");
+                    let _ = writeln!(writer);
+                }
+
                 if let Some(crate_id) = next_path.crate_id {
                     let crate_ = api.crates.get(crate_id).unwrap();
                     let _ = writeln!(writer, "*from crate `{}`*", crate_.name);
@@ -135,12 +175,9 @@ pub fn render_api_markdown(
                 writer,
                 "- `{}` (version {})",
                 crate_.name,
-                crate_
-                    .version
-                    .as_ref()
-                    .map(|v| v.to_string())
-                    .unwrap_or_else(|| "unknown".into())
+                crate_version(crate_)
             );
+            render_exposed_by(writer, api, crate_);
         }
         let _ = writeln!(writer);
     }
@@ -148,13 +185,133 @@ pub fn render_api_markdown(
     Ok(())
 }
 
+pub fn render_api_text(
+    writer: &mut dyn std::io::Write,
+    api: &crate_api::Api,
+) -> Result<(), eyre::Report> {
+    // Sorted and de-duplicated so the output only changes when the API does
+    let lines: std::collections::BTreeSet<_> = api
+        .paths
+        .iter()
+        .map(|(_, path)| crate_api::rustdoc::render_declaration(path))
+        .collect();
+    for line in lines {
+        let _ = writeln!(writer, "{}", line);
+    }
+
+    Ok(())
+}
+
+/// A package's part of a workspace-wide report
+pub struct Section<'s> {
+    pub name: &'s str,
+    pub summary: String,
+    /// The package's report, without anything wrapping it
+    pub body: Vec<u8>,
+}
+
+pub fn render_workspace_markdown(
+    writer: &mut dyn std::io::Write,
+    sections: &[Section<'_>],
+    failed: usize,
+) {
+    let _ = writeln!(writer, "# Workspace");
+    let _ = writeln!(writer);
+    let _ = writeln!(writer, "| Package | Summary |");
+    let _ = writeln!(writer, "| ------- | ------- |");
+    for section in sections {
+        let _ = writeln!(writer, "| `{}` | {} |", section.name, section.summary);
+    }
+    let _ = writeln!(writer);
+    if 0 < failed {
+        let _ = writeln!(
+            writer,
+            "{} failed, see the log for details",
+            plural(failed, "package", "packages")
+        );
+        let _ = writeln!(writer);
+    }
+
+    for section in sections {
+        let _ = writeln!(writer, "## `{}`", section.name);
+        let _ = writeln!(writer);
+        // Nest the package's headings under its section
+        let mut in_code = false;
+        for line in String::from_utf8_lossy(&section.body).lines() {
+            if line.starts_with("```") {
+                in_code = !in_code;
+            }
+            if !in_code && line.starts_with('#') {
+                let _ = writeln!(writer, "#{}", line);
+            } else {
+                let _ = writeln!(writer, "{}", line);
+            }
+        }
+        let _ = writeln!(writer);
+    }
+}
+
+pub fn raw_summary(raw: &Raw) -> String {
+    let items = raw
+        .rustdoc
+        .as_ref()
+        .map(|rustdoc| rustdoc.index.len())
+        .unwrap_or_default();
+    plural(items, "item", "items")
+}
+
+pub fn api_summary(api: &crate_api::Api) -> String {
+    format!(
+        "{}, {}",
+        plural(api.paths.len(), "public path", "public paths"),
+        plural(api.crates.len(), "public dependency", "public dependencies")
+    )
+}
+
+pub fn diff_summary(report: &Diff) -> String {
+    let count = |severity| {
+        report
+            .diffs
+            .iter()
+            .filter(|diff| diff.severity == severity)
+            .count()
+    };
+    let breaking = count(crate_api::diff::Severity::Warn);
+    let other = count(crate_api::diff::Severity::Report);
+    if breaking == 0 && other == 0 {
+        "No changes".to_owned()
+    } else {
+        format!(
+            "{}, {}",
+            plural(breaking, "breaking change", "breaking changes"),
+            plural(other, "other change", "other changes")
+        )
+    }
+}
+
+pub fn is_breaking(report: &Diff) -> bool {
+    report
+        .diffs
+        .iter()
+        .any(|diff| diff.severity == crate_api::diff::Severity::Warn)
+}
+
+pub fn plural(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, singular)
+    } else {
+        format!("{} {}", count, plural)
+    }
+}
+
 pub fn render_diff_markdown(
     writer: &mut dyn std::io::Write,
-    before: &crate_api::Api,
-    after: &crate_api::Api,
-    diffs: &[crate_api::diff::Diff],
+    report: &Diff,
 ) -> Result<(), eyre::Report> {
-    let mut diffs = diffs.to_vec();
+    render_diff_markdown_header(writer, report);
+    let _ = writeln!(writer);
+
+    let mut diffs = report.diffs.clone();
     diffs.sort_by_key(|diff| (diff.severity, diff.id.category, diff.id.name));
 
     let mut last_severity = crate_api::diff::Severity::Allow;
@@ -190,38 +347,569 @@ pub fn render_diff_markdown(
             last_category = Some(diff.id.category);
         }
 
-        match diff.id {
-            crate_api::diff::DEPENDENCY_REQUIREMENT => {
-                let before_crate = before
-                    .crates
-                    .get(diff.before.unwrap().crate_id.unwrap())
-                    .unwrap();
-                let after_crate = after
-                    .crates
-                    .get(diff.after.unwrap().crate_id.unwrap())
-                    .unwrap();
-                let _ = writeln!(
-                    writer,
-                    "- `{}` (public dependency): changed version requirement from {} to {}",
-                    after_crate.name,
-                    before_crate.version.as_ref().unwrap(),
-                    after_crate.version.as_ref().unwrap()
-                );
+        let _ = writeln!(writer, "- {}", diff_message(report, &diff));
+        if let Some(crate_api::diff::Details::Dependency { exposed_by, .. }) = diff.details.as_ref()
+        {
+            for path in exposed_by {
+                let _ = writeln!(writer, "  - exposed by `{}`", path);
             }
-            _ => {
-                let name = diff
-                    .after
-                    .map(|loc| location_name(after, loc))
-                    .or_else(|| diff.before.map(|loc| location_name(before, loc)))
-                    .expect("at least before or after exists");
-                let _ = writeln!(writer, "- `{}`: {}", name, diff.id.explanation);
+        }
+    }
+
+    Ok(())
+}
+
+/// Describe what was compared
+fn render_diff_markdown_header(writer: &mut dyn std::io::Write, report: &Diff) {
+    let against = match report.commit.as_deref() {
+        Some(commit) => format!("{} ({})", report.against, commit),
+        None => report.against.to_string(),
+    };
+    match (report.head.as_deref(), report.head_commit.as_deref()) {
+        (Some(head), Some(commit)) => {
+            let _ = writeln!(
+                writer,
+                "Compared `{}` ({}) against {}",
+                head, commit, against
+            );
+        }
+        _ => {
+            let _ = writeln!(writer, "Compared against {}", against);
+        }
+    }
+}
+
+/// Render a SARIF log with a run per package
+pub fn render_diff_sarif(
+    writer: &mut dyn std::io::Write,
+    reports: &[&Diff],
+) -> Result<(), eyre::Report> {
+    let runs: Vec<_> = reports.iter().map(|report| sarif_run(report)).collect();
+    let sarif = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": runs,
+    });
+    let _ = writeln!(writer, "{}", serde_json::to_string_pretty(&sarif)?);
+
+    Ok(())
+}
+
+fn sarif_run(report: &Diff) -> serde_json::Value {
+    let mut ids: Vec<_> = report.diffs.iter().map(|diff| diff.id).collect();
+    ids.sort_by_key(|id| id.name);
+    ids.dedup();
+    let rules: Vec<_> = ids
+        .iter()
+        .map(|id| {
+            serde_json::json!({
+                "id": id.name,
+                "shortDescription": { "text": id.explanation },
+                "defaultConfiguration": { "level": sarif_level(id.default_severity) },
+                "properties": { "category": id.category },
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = report
+        .diffs
+        .iter()
+        .map(|diff| {
+            let rule_index = ids
+                .iter()
+                .position(|id| *id == diff.id)
+                .expect("all ids are rules");
+            let span = diff_span(report, diff);
+            let physical = match (span, report.manifest_path.as_deref()) {
+                (Some(span), _) => {
+                    let path = span_path(report.workspace_root.as_deref(), span);
+                    let region = span_region(span);
+                    Some(serde_json::json!({
+                        "artifactLocation": sarif_artifact(report, &path),
+                        "region": {
+                            "startLine": region.start_line,
+                            "startColumn": region.start_column,
+                            "endLine": region.end_line,
+                            "endColumn": region.end_column,
+                        },
+                    }))
+                }
+                // Changes without source, like to dependencies, come from the manifest
+                (None, Some(manifest_path)) => Some(serde_json::json!({
+                    "artifactLocation": sarif_artifact(report, manifest_path),
+                })),
+                (None, None) => None,
+            };
+            let mut location = serde_json::json!({
+                "logicalLocations": [{ "fullyQualifiedName": diff_name(report, diff) }],
+            });
+            if let Some(physical) = physical {
+                location["physicalLocation"] = physical;
+            }
+            serde_json::json!({
+                "ruleId": diff.id.name,
+                "ruleIndex": rule_index,
+                "level": sarif_level(diff.severity),
+                "message": { "text": diff_message(report, diff) },
+                "locations": [location],
+            })
+        })
+        .collect();
+
+    let mut run = serde_json::json!({
+        "tool": {
+            "driver": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+                "informationUri": env!("CARGO_PKG_REPOSITORY").trim_end_matches(".git"),
+                "rules": rules,
+            },
+        },
+        "results": results,
+    });
+    if let Some(workspace_root) = report.workspace_root.as_deref() {
+        run["originalUriBaseIds"] = serde_json::json!({
+            SARIF_SRCROOT: { "uri": format!("{}/", file_uri(workspace_root).trim_end_matches('/')) },
+        });
+    }
+    run
+}
+
+/// The base id that workspace-relative artifacts are relative to
+const SARIF_SRCROOT: &str = "%SRCROOT%";
+
+/// Point at `path`, relative to the workspace when it's within it
+fn sarif_artifact(report: &Diff, path: &std::path::Path) -> serde_json::Value {
+    match report
+        .workspace_root
+        .as_deref()
+        .and_then(|root| path.strip_prefix(root).ok())
+    {
+        Some(relative) => serde_json::json!({
+            "uri": file_uri(relative),
+            "uriBaseId": SARIF_SRCROOT,
+        }),
+        None => serde_json::json!({ "uri": file_uri(path) }),
+    }
+}
+
+pub fn render_diff_github(
+    writer: &mut dyn std::io::Write,
+    report: &Diff,
+) -> Result<(), eyre::Report> {
+    let root = std::env::var_os("GITHUB_WORKSPACE")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::current_dir().ok());
+    render_github_annotations(writer, report, root.as_deref());
+
+    Ok(())
+}
+
+/// Write workflow commands with files relative to the checkout at `root`
+fn render_github_annotations(
+    writer: &mut dyn std::io::Write,
+    report: &Diff,
+    root: Option<&std::path::Path>,
+) {
+    let relative = |path: &std::path::Path| -> String {
+        let path = root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        path.to_string_lossy().replace('\\', "/")
+    };
+
+    for diff in &report.diffs {
+        let level = match diff.severity {
+            crate_api::diff::Severity::Allow => continue,
+            crate_api::diff::Severity::Report => "notice",
+            crate_api::diff::Severity::Warn => "error",
+        };
+        let mut properties = Vec::new();
+        match (diff_span(report, diff), report.manifest_path.as_deref()) {
+            (Some(span), _) => {
+                let path = span_path(report.workspace_root.as_deref(), span);
+                let region = span_region(span);
+                properties.push(("file", relative(&path)));
+                properties.push(("line", region.start_line.to_string()));
+                properties.push(("endLine", region.end_line.to_string()));
+                if region.start_line == region.end_line {
+                    properties.push(("col", region.start_column.to_string()));
+                    properties.push(("endColumn", region.end_column.to_string()));
+                }
             }
+            (None, Some(manifest_path)) => {
+                properties.push(("file", relative(manifest_path)));
+            }
+            (None, None) => {}
         }
+        properties.push(("title", diff.id.name.to_owned()));
+        let properties = properties
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, escape_github_property(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let _ = writeln!(
+            writer,
+            "::{} {}::{}",
+            level,
+            properties,
+            escape_github_data(&diff_message(report, diff))
+        );
     }
+}
+
+/// Escape the message of a GitHub workflow command
+fn escape_github_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property of a GitHub workflow command
+fn escape_github_property(value: &str) -> String {
+    escape_github_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+pub fn render_junit_header(writer: &mut dyn std::io::Write) {
+    let _ = writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(writer, r#"<testsuites name="{}">"#, env!("CARGO_PKG_NAME"));
+}
+
+pub fn render_junit_footer(writer: &mut dyn std::io::Write) {
+    let _ = writeln!(writer, "</testsuites>");
+}
+
+/// Render a `<testsuite>` with a test case per rule, failing for breaking changes
+///
+/// This goes between [`render_junit_header`] and [`render_junit_footer`].
+pub fn render_diff_junit(
+    writer: &mut dyn std::io::Write,
+    report: &Diff,
+) -> Result<(), eyre::Report> {
+    let suite = report
+        .after
+        .root_id
+        .and_then(|root_id| report.after.paths.get(root_id))
+        .map(|root| root.path.as_str())
+        .unwrap_or("api");
+    let is_failure =
+        |diff: &crate_api::diff::Diff| diff.severity == crate_api::diff::Severity::Warn;
+    let failures = crate_api::diff::ALL_IDS
+        .iter()
+        .filter(|id| {
+            report
+                .diffs
+                .iter()
+                .any(|diff| diff.id == **id && is_failure(diff))
+        })
+        .count();
+
+    let _ = writeln!(
+        writer,
+        r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+        escape_markup(suite),
+        crate_api::diff::ALL_IDS.len(),
+        failures
+    );
+    for id in crate_api::diff::ALL_IDS {
+        let diffs: Vec<_> = report.diffs.iter().filter(|diff| diff.id == *id).collect();
+        let _ = writeln!(
+            writer,
+            r#"    <testcase classname="{}" name="{}">"#,
+            escape_markup(suite),
+            escape_markup(id.name)
+        );
+
+        let mut failed = Vec::new();
+        let mut reported = Vec::new();
+        for diff in diffs {
+            let mut line = diff_message(report, diff);
+            if let Some(span) = diff_span(report, diff) {
+                let path = span_path(report.workspace_root.as_deref(), span);
+                line.push_str(&format!(
+                    " at {}:{}",
+                    path.display(),
+                    span_region(span).start_line
+                ));
+            }
+            if is_failure(diff) {
+                failed.push(line);
+            } else {
+                reported.push(line);
+            }
+        }
+        if !failed.is_empty() {
+            let _ = writeln!(
+                writer,
+                r#"      <failure message="{}" type="{}">{}</failure>"#,
+                escape_markup(id.explanation),
+                escape_markup(id.name),
+                escape_markup(&failed.join("\n"))
+            );
+        }
+        if !reported.is_empty() {
+            let _ = writeln!(
+                writer,
+                "      <system-out>{}</system-out>",
+                escape_markup(&reported.join("\n"))
+            );
+        }
+        let _ = writeln!(writer, "    </testcase>");
+    }
+    let _ = writeln!(writer, "  </testsuite>");
 
     Ok(())
 }
 
+/// Render a `<testsuite>` for a package that couldn't be compared, erroring its one test case
+///
+/// This goes between [`render_junit_header`] and [`render_junit_footer`].
+pub fn render_error_junit(writer: &mut dyn std::io::Write, name: &str, message: &str) {
+    let _ = writeln!(
+        writer,
+        r#"  <testsuite name="{}" tests="1" failures="0" errors="1">"#,
+        escape_markup(name)
+    );
+    let _ = writeln!(
+        writer,
+        r#"    <testcase classname="{}" name="api">"#,
+        escape_markup(name)
+    );
+    let _ = writeln!(
+        writer,
+        r#"      <error message="{}">{}</error>"#,
+        escape_markup(message.lines().next().unwrap_or_default()),
+        escape_markup(message)
+    );
+    let _ = writeln!(writer, "    </testcase>");
+    let _ = writeln!(writer, "  </testsuite>");
+}
+
+/// Escape text and attribute values for HTML and XML
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn sarif_level(severity: crate_api::diff::Severity) -> &'static str {
+    match severity {
+        crate_api::diff::Severity::Allow => "none",
+        crate_api::diff::Severity::Report => "note",
+        crate_api::diff::Severity::Warn => "warning",
+    }
+}
+
+fn file_uri(path: &std::path::Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        path
+    }
+}
+
+const CHANGELOG_HEADINGS: &[&str] = &["Added", "Changed", "Removed"];
+
+/// Keep a Changelog sections, in order, describing `report`
+///
+/// Changes that don't fit a category are listed as `Changed`.
+pub fn changelog_sections(report: &Diff) -> Vec<(&'static str, Vec<String>)> {
+    let mut sections: Vec<(&'static str, Vec<String>)> = CHANGELOG_HEADINGS
+        .iter()
+        .map(|heading| (*heading, Vec::new()))
+        .collect();
+    let mut diffs = report.diffs.clone();
+    diffs.sort_by_key(|diff| std::cmp::Reverse(diff.severity));
+    for diff in diffs {
+        let index = match diff.id.category {
+            crate_api::diff::Category::Added => 0,
+            crate_api::diff::Category::Unknown | crate_api::diff::Category::Changed => 1,
+            crate_api::diff::Category::Removed => 2,
+        };
+        let entry = match diff.severity {
+            crate_api::diff::Severity::Allow => continue,
+            crate_api::diff::Severity::Report => diff_message(report, &diff),
+            crate_api::diff::Severity::Warn => {
+                format!("**Breaking:** {}", diff_message(report, &diff))
+            }
+        };
+        sections[index].1.push(entry);
+    }
+    sections.retain(|(_, entries)| !entries.is_empty());
+    sections
+}
+
+/// Keep a Changelog sections, in order, describing each package's report
+///
+/// Entries are prefixed with their package so they can be told apart.
+pub fn workspace_changelog_sections(reports: &[(&str, &Diff)]) -> Vec<(&'static str, Vec<String>)> {
+    let mut sections: Vec<(&'static str, Vec<String>)> = Vec::new();
+    for (name, report) in reports {
+        for (heading, entries) in changelog_sections(report) {
+            let entries = entries
+                .into_iter()
+                .map(|entry| format!("`{}`: {}", name, entry));
+            match sections.iter_mut().find(|(other, _)| *other == heading) {
+                Some((_, existing)) => existing.extend(entries),
+                None => sections.push((heading, entries.collect())),
+            }
+        }
+    }
+    sections.sort_by_key(|(heading, _)| CHANGELOG_HEADINGS.iter().position(|h| h == heading));
+    sections
+}
+
+pub fn render_diff_changelog(
+    writer: &mut dyn std::io::Write,
+    report: &Diff,
+) -> Result<(), eyre::Report> {
+    render_changelog_sections(writer, &changelog_sections(report));
+    Ok(())
+}
+
+pub fn render_changelog_sections(
+    writer: &mut dyn std::io::Write,
+    sections: &[(&'static str, Vec<String>)],
+) {
+    for (heading, entries) in sections {
+        let _ = writeln!(writer, "### {}", heading);
+        let _ = writeln!(writer);
+        for entry in entries {
+            let _ = writeln!(writer, "- {}", entry);
+        }
+        let _ = writeln!(writer);
+    }
+}
+
+/// Describe `diff` in a single line
+fn diff_message(report: &Diff, diff: &crate_api::diff::Diff) -> String {
+    match (diff.id, diff.details.as_ref()) {
+        (
+            crate_api::diff::DEPENDENCY_REQUIREMENT,
+            Some(crate_api::diff::Details::DependencyVersion {
+                name,
+                before,
+                after,
+            }),
+        ) => format!(
+            "`{}` (public dependency): changed version requirement from {} to {}",
+            name, before, after
+        ),
+        (
+            _,
+            Some(crate_api::diff::Details::ExposedDependency {
+                path,
+                name,
+                before,
+                after,
+            }),
+        ) => format!(
+            "`{}`: {} (`{}` {} to {})",
+            path, diff.id.explanation, name, before, after
+        ),
+        _ => format!("`{}`: {}", diff_name(report, diff), diff.id.explanation),
+    }
+}
+
+/// What `diff` is about, like the path of an item
+fn diff_name<'r>(report: &'r Diff, diff: &'r crate_api::diff::Diff) -> &'r str {
+    match diff.details.as_ref() {
+        Some(crate_api::diff::Details::DependencyVersion { name, .. })
+        | Some(crate_api::diff::Details::Dependency { name, .. }) => name,
+        Some(crate_api::diff::Details::ExposedDependency { path, .. }) => path,
+        _ => diff
+            .after
+            .map(|loc| location_name(&report.after, loc))
+            .or_else(|| diff.before.map(|loc| location_name(&report.before, loc)))
+            .expect("at least before or after exists"),
+    }
+}
+
+fn render_exposed_by(
+    writer: &mut dyn std::io::Write,
+    api: &crate_api::Api,
+    crate_: &crate_api::Crate,
+) {
+    let mut paths: Vec<_> = crate_
+        .exposed_by
+        .iter()
+        .map(|path_id| api.paths.get(*path_id).unwrap().path.as_str())
+        .collect();
+    paths.sort_unstable();
+    for path in paths {
+        let _ = writeln!(writer, "  - exposed by `{}`", path);
+    }
+}
+
+fn crate_version(crate_: &crate_api::Crate) -> String {
+    match (crate_.version.as_ref(), crate_.resolved.as_ref()) {
+        (Some(version), Some(resolved)) => format!("{} ({})", version, resolved),
+        (Some(version), None) => version.to_string(),
+        (None, Some(resolved)) => resolved.to_string(),
+        (None, None) => "unknown".to_owned(),
+    }
+}
+
+/// The file `span` is in
+///
+/// rustdoc's spans are relative to the workspace root, rather than the package.  Without the
+/// root, like for APIs extracted ahead of time, they're left relative.
+fn span_path(
+    workspace_root: Option<&std::path::Path>,
+    span: &crate_api::Span,
+) -> std::path::PathBuf {
+    match workspace_root {
+        Some(root) => root.join(&span.filename),
+        None => span.filename.clone(),
+    }
+}
+
+/// A source region, with 1-indexed lines and columns as editors and CI services expect
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+fn span_region(span: &crate_api::Span) -> Region {
+    // rustdoc's lines are 1-indexed and columns are 0-indexed
+    Region {
+        start_line: span.begin.0,
+        start_column: span.begin.1 + 1,
+        end_line: span.end.0,
+        end_column: span.end.1 + 1,
+    }
+}
+
+/// Where `diff` is in the source, preferring the new code
+fn diff_span<'r>(report: &'r Diff, diff: &crate_api::diff::Diff) -> Option<&'r crate_api::Span> {
+    diff.after
+        .and_then(|loc| location_span(&report.after, loc))
+        .or_else(|| {
+            diff.before
+                .and_then(|loc| location_span(&report.before, loc))
+        })
+}
+
+fn location_span(
+    api: &crate_api::Api,
+    location: crate_api::diff::Location,
+) -> Option<&crate_api::Span> {
+    if let Some(path_id) = location.path_id {
+        api.paths.get(path_id).and_then(|path| path.span.as_ref())
+    } else if let Some(item_id) = location.item_id {
+        api.items.get(item_id).and_then(|item| item.span.as_ref())
+    } else {
+        None
+    }
+}
+
 fn location_name(api: &crate_api::Api, location: crate_api::diff::Location) -> &str {
     if let Some(path_id) = location.path_id {
         api.paths.get(path_id).unwrap().path.as_str()
@@ -233,3 +921,44 @@ fn location_name(api: &crate_api::Api, location: crate_api::diff::Location) -> &
         unimplemented!("{:?} had no location", location)
     }
 }
+
+#[test]
+fn text_lines_are_qualified() {
+    let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures");
+    let render = |name: &str| -> Vec<String> {
+        let path = fixture.join(name).join("new/rustdoc-api.json");
+        let api: crate_api::Api =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let mut text = Vec::new();
+        render_api_text(&mut text, &api).unwrap();
+        String::from_utf8(text)
+            .unwrap()
+            .lines()
+            .map(|line| line.to_owned())
+            .collect()
+    };
+
+    let lines = render("addition_path");
+    let mut sorted = lines.clone();
+    sorted.sort();
+    assert_eq!(lines, sorted);
+    for expected in [
+        "pub mod addition_path_new::b",
+        "pub struct addition_path_new::a::Abc;",
+        "pub use addition_path_new::Abc; // addition_path_new::a::Abc",
+    ] {
+        assert!(lines.iter().any(|line| line == expected), "{:#?}", lines);
+    }
+
+    let lines = render("enums");
+    let expected = "pub enum enums_new::Efg { Abc(u8), Bcd, Cde { f: u8 }, Def, Efg { f: u8 }, Fgh { f: u16 }, Ghi { g: u8 } }";
+    assert!(lines.iter().any(|line| line == expected), "{:#?}", lines);
+
+    let lines = render("macros");
+    let expected = "macro_rules! macros_new::bar { () => { ... }; }";
+    assert!(lines.iter().any(|line| line == expected), "{:#?}", lines);
+
+    let lines = render("structs");
+    let expected = "#[non_exhaustive] pub struct structs_new::Mno { /* some fields omitted */ }";
+    assert!(lines.iter().any(|line| line == expected), "{:#?}", lines);
+}
@@ -0,0 +1,390 @@
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em auto; max-width: 70em; padding: 0 1em; }
+code { font-family: monospace; }
+details > ul, ul.items { list-style: none; margin: 0; padding-left: 1.5em; }
+summary { cursor: pointer; }
+.kind { color: #666; font-size: smaller; }
+.from { color: #666; font-style: italic; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+tr.warn td:first-child { border-left: 4px solid #d73a49; }
+tr.report td:first-child { border-left: 4px solid #0366d6; }
+td pre { margin: 0.3em 0; white-space: pre-wrap; }
+";
+
+pub fn render_api_html(
+    writer: &mut dyn std::io::Write,
+    report: &super::Api,
+) -> Result<(), eyre::Report> {
+    let api = &report.api;
+    let title = api
+        .root_id
+        .and_then(|root_id| api.paths.get(root_id))
+        .map(|root| root.path.as_str())
+        .unwrap_or("API");
+    render_header(writer, title);
+    render_api_html_body(writer, report, 1);
+    render_footer(writer);
+    Ok(())
+}
+
+/// Render the API without a document around it, starting at `<h{level}>`
+pub fn render_api_html_body(writer: &mut dyn std::io::Write, report: &super::Api, level: usize) {
+    let api = &report.api;
+    let workspace_root = report.workspace_root.as_deref();
+
+    if let Some(root) = api.root_id.and_then(|root_id| api.paths.get(root_id)) {
+        let _ = writeln!(
+            writer,
+            "<h{level}><code>{}</code></h{level}>",
+            super::escape_markup(&root.path),
+            level = level
+        );
+        let _ = writeln!(writer, "<ul class=\"items\">");
+        for child in sorted_children(api, &root.children) {
+            render_path(writer, api, child, workspace_root);
+        }
+        let _ = writeln!(writer, "</ul>");
+    }
+
+    if !api.features.is_empty() {
+        let _ = writeln!(
+            writer,
+            "<h{level}>Feature Flags</h{level}>",
+            level = level + 1
+        );
+        let _ = writeln!(writer, "<table>");
+        let _ = writeln!(writer, "<tr><th>Feature</th><th>Enables</th></tr>");
+        for details in api.features.values() {
+            match details {
+                crate_api::AnyFeature::Feature(feature) => {
+                    let enables = feature
+                        .dependencies
+                        .iter()
+                        .map(|dep| format!("<code>{}</code>", super::escape_markup(dep)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let _ = writeln!(
+                        writer,
+                        "<tr><td><code>{}</code></td><td>{}</td></tr>",
+                        super::escape_markup(&feature.name),
+                        enables
+                    );
+                }
+                crate_api::AnyFeature::OptionalDependency(dep) => {
+                    let package = dep.package.as_deref().unwrap_or(&dep.name);
+                    let _ = writeln!(
+                        writer,
+                        "<tr><td><code>{}</code></td><td>dependency <code>{}</code></td></tr>",
+                        super::escape_markup(&dep.name),
+                        super::escape_markup(package)
+                    );
+                }
+            }
+        }
+        let _ = writeln!(writer, "</table>");
+    }
+
+    if !api.crates.is_empty() {
+        let _ = writeln!(
+            writer,
+            "<h{level}>Public Dependencies</h{level}>",
+            level = level + 1
+        );
+        let _ = writeln!(writer, "<table>");
+        let _ = writeln!(
+            writer,
+            "<tr><th>Crate</th><th>Version</th><th>Exposed by</th></tr>"
+        );
+        for (_, crate_) in api.crates.iter() {
+            let mut exposed_by: Vec<_> = crate_
+                .exposed_by
+                .iter()
+                .filter_map(|path_id| api.paths.get(*path_id))
+                .map(|path| format!("<code>{}</code>", super::escape_markup(&path.path)))
+                .collect();
+            exposed_by.sort_unstable();
+            let _ = writeln!(
+                writer,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                super::escape_markup(&crate_.name),
+                super::escape_markup(&super::crate_version(crate_)),
+                exposed_by.join("<br>")
+            );
+        }
+        let _ = writeln!(writer, "</table>");
+    }
+}
+
+pub fn render_diff_html(
+    writer: &mut dyn std::io::Write,
+    report: &super::Diff,
+) -> Result<(), eyre::Report> {
+    render_header(writer, "API Changes");
+    render_diff_html_body(writer, report, 1);
+    render_footer(writer);
+    Ok(())
+}
+
+/// Render the changes without a document around them, starting at `<h{level}>`
+pub fn render_diff_html_body(writer: &mut dyn std::io::Write, report: &super::Diff, level: usize) {
+    let workspace_root = report.workspace_root.as_deref();
+
+    let _ = writeln!(writer, "<h{level}>API Changes</h{level}>", level = level);
+    let mut compared = Vec::new();
+    super::render_diff_markdown_header(&mut compared, report);
+    let _ = writeln!(
+        writer,
+        "<p>{}</p>",
+        inline_code(String::from_utf8_lossy(&compared).trim())
+    );
+
+    let mut diffs = report.diffs.clone();
+    diffs.sort_by_key(|diff| {
+        (
+            std::cmp::Reverse(diff.severity),
+            diff.id.category,
+            diff.id.name,
+        )
+    });
+    if diffs.is_empty() {
+        let _ = writeln!(writer, "<p>No changes</p>");
+    } else {
+        let _ = writeln!(writer, "<table>");
+        let _ = writeln!(
+            writer,
+            "<tr><th>Change</th><th>Before</th><th>After</th></tr>"
+        );
+        for diff in &diffs {
+            let class = match diff.severity {
+                crate_api::diff::Severity::Allow => "allow",
+                crate_api::diff::Severity::Report => "report",
+                crate_api::diff::Severity::Warn => "warn",
+            };
+            let (before_version, after_version) = match diff.details.as_ref() {
+                Some(crate_api::diff::Details::DependencyVersion { before, after, .. })
+                | Some(crate_api::diff::Details::ExposedDependency { before, after, .. }) => {
+                    (Some(before.to_string()), Some(after.to_string()))
+                }
+                _ => (None, None),
+            };
+            let before = render_side(
+                &report.before,
+                diff.before,
+                before_version.as_deref(),
+                workspace_root,
+            );
+            let after = render_side(
+                &report.after,
+                diff.after,
+                after_version.as_deref(),
+                workspace_root,
+            );
+            let _ = writeln!(
+                writer,
+                "<tr class=\"{}\"><td title=\"{}\">{}<br><span class=\"kind\">{}</span></td><td>{}</td><td>{}</td></tr>",
+                class,
+                super::escape_markup(diff.id.explanation),
+                inline_code(&super::diff_message(report, diff)),
+                super::escape_markup(diff.id.name),
+                before,
+                after
+            );
+        }
+        let _ = writeln!(writer, "</table>");
+    }
+}
+
+/// Render a document with a section per package
+pub fn render_workspace_html(
+    writer: &mut dyn std::io::Write,
+    sections: &[super::Section<'_>],
+    failed: usize,
+) {
+    render_header(writer, "Workspace");
+
+    let _ = writeln!(writer, "<h1>Workspace</h1>");
+    let _ = writeln!(writer, "<table>");
+    let _ = writeln!(writer, "<tr><th>Package</th><th>Summary</th></tr>");
+    for section in sections {
+        let _ = writeln!(
+            writer,
+            "<tr><td><a href=\"#{}\"><code>{}</code></a></td><td>{}</td></tr>",
+            super::escape_markup(section.name),
+            super::escape_markup(section.name),
+            inline_code(&section.summary)
+        );
+    }
+    let _ = writeln!(writer, "</table>");
+    if 0 < failed {
+        let _ = writeln!(
+            writer,
+            "<p>{} failed, see the log for details</p>",
+            super::plural(failed, "package", "packages")
+        );
+    }
+
+    for section in sections {
+        let _ = writeln!(
+            writer,
+            "<section id=\"{}\">",
+            super::escape_markup(section.name)
+        );
+        let _ = writeln!(
+            writer,
+            "<h2><code>{}</code></h2>",
+            super::escape_markup(section.name)
+        );
+        let _ = writer.write_all(&section.body);
+        let _ = writeln!(writer, "</section>");
+    }
+
+    render_footer(writer);
+}
+
+fn render_header(writer: &mut dyn std::io::Write, title: &str) {
+    let _ = writeln!(writer, "<!DOCTYPE html>");
+    let _ = writeln!(writer, "<html>");
+    let _ = writeln!(writer, "<head>");
+    let _ = writeln!(writer, "<meta charset=\"utf-8\">");
+    let _ = writeln!(writer, "<title>{}</title>", super::escape_markup(title));
+    let _ = writeln!(writer, "<style>{}</style>", STYLE);
+    let _ = writeln!(writer, "</head>");
+    let _ = writeln!(writer, "<body>");
+}
+
+fn render_footer(writer: &mut dyn std::io::Write) {
+    let _ = writeln!(writer, "</body>");
+    let _ = writeln!(writer, "</html>");
+}
+
+fn render_path(
+    writer: &mut dyn std::io::Write,
+    api: &crate_api::Api,
+    path_id: crate_api::PathId,
+    workspace_root: Option<&std::path::Path>,
+) {
+    let path = api.paths.get(path_id).unwrap();
+    let mut label = format!(
+        "<code>{}</code> <span class=\"kind\">{:?}</span>",
+        super::escape_markup(&path.path),
+        path.kind
+    );
+    if let Some(crate_) = path.crate_id.and_then(|crate_id| api.crates.get(crate_id)) {
+        label.push_str(&format!(
+            " <span class=\"from\">from crate <code>{}</code></span>",
+            super::escape_markup(&crate_.name)
+        ));
+    }
+    if let Some(span) = path.span.as_ref() {
+        label.push(' ');
+        label.push_str(&render_span(span, workspace_root));
+    }
+
+    if path.children.is_empty() {
+        let _ = writeln!(writer, "<li>{}</li>", label);
+    } else {
+        let open = if path.kind == crate_api::PathKind::Module {
+            " open"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            writer,
+            "<li><details{}><summary>{}</summary><ul>",
+            open, label
+        );
+        for child in sorted_children(api, &path.children) {
+            render_path(writer, api, child, workspace_root);
+        }
+        let _ = writeln!(writer, "</ul></details></li>");
+    }
+}
+
+/// Modules last, like in the markdown report
+fn sorted_children(api: &crate_api::Api, children: &[crate_api::PathId]) -> Vec<crate_api::PathId> {
+    let mut children = children.to_vec();
+    children.sort_unstable_by_key(|path_id| {
+        let path = api.paths.get(*path_id).unwrap();
+        (
+            path.kind == crate_api::PathKind::Module,
+            path.kind,
+            path.path.clone(),
+        )
+    });
+    children
+}
+
+/// Render one side of a change, with the dependency's version when it's about one
+fn render_side(
+    api: &crate_api::Api,
+    location: Option<crate_api::diff::Location>,
+    version: Option<&str>,
+    workspace_root: Option<&std::path::Path>,
+) -> String {
+    let mut rendered = location
+        .map(|location| render_location(api, location, workspace_root))
+        .unwrap_or_default();
+    if let Some(version) = version {
+        if !rendered.is_empty() && !rendered.ends_with("</pre>") {
+            rendered.push_str("<br>");
+        }
+        rendered.push_str(&super::escape_markup(version));
+    }
+    rendered
+}
+
+/// Render the item at `location` with its signature, linking to its source below it
+fn render_location(
+    api: &crate_api::Api,
+    location: crate_api::diff::Location,
+    workspace_root: Option<&std::path::Path>,
+) -> String {
+    let mut rendered = format!(
+        "<code>{}</code>",
+        super::escape_markup(super::location_name(api, location))
+    );
+    let path = location.path_id.and_then(|path_id| api.paths.get(path_id));
+    if let Some(path) = path {
+        rendered.push_str(&format!(" <span class=\"kind\">{:?}</span>", path.kind));
+    }
+    let signature = path.and_then(|path| path.signature.as_deref());
+    if let Some(signature) = signature {
+        rendered.push_str(&format!(
+            "<pre><code>{}</code></pre>",
+            super::escape_markup(signature)
+        ));
+    }
+    if let Some(span) = super::location_span(api, location) {
+        if signature.is_none() {
+            rendered.push_str("<br>");
+        }
+        rendered.push_str(&render_span(span, workspace_root));
+    }
+    rendered
+}
+
+fn render_span(span: &crate_api::Span, workspace_root: Option<&std::path::Path>) -> String {
+    let href = super::file_uri(&super::span_path(workspace_root, span));
+    format!(
+        "<a href=\"{}\">{}:{}</a>",
+        super::escape_markup(&href),
+        super::escape_markup(&span.filename.to_string_lossy()),
+        super::span_region(span).start_line
+    )
+}
+
+/// Render markdown's `code` spans
+fn inline_code(text: &str) -> String {
+    super::escape_markup(text)
+        .split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                format!("<code>{}</code>", part)
+            } else {
+                part.to_owned()
+            }
+        })
+        .collect()
+}
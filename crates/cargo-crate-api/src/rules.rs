@@ -0,0 +1,210 @@
+use crate_api::diff::Id;
+
+struct Rule {
+    id: Id,
+    description: &'static str,
+    before: &'static str,
+    after: &'static str,
+}
+
+// Examples are adapted from `fixtures/dep_upgrade`
+const RULES: &[Rule] = &[
+    Rule {
+        id: crate_api::diff::DEPENDENCY_REMOVED,
+        description: "\
+A dependency's types no longer appear in the public API.
+
+This is reported for completeness: the removal itself is the result of other API
+changes, like a function no longer taking a type from the dependency, and those
+are what break callers.",
+        before: "\
+# Cargo.toml
+[dependencies]
+dep = { version = \"1.0\", package = \"dep_upgrade_dep\" }
+
+// src/lib.rs
+pub fn in_func(_var: dep::InFunc) {}",
+        after: "\
+# Cargo.toml
+[dependencies]
+dep = { version = \"1.0\", package = \"dep_upgrade_dep\" }
+
+// src/lib.rs
+pub fn in_func(_var: u8) {}",
+    },
+    Rule {
+        id: crate_api::diff::DEPENDENCY_ADDED,
+        description: "\
+A dependency's types now appear in the public API.
+
+This doesn't break callers but it ties your compatibility to the dependency's: a
+future major version bump of the dependency becomes a breaking change for you.
+It is reported in case the dependency was exposed by accident.",
+        before: "\
+# Cargo.toml
+[dependencies]
+dep = { version = \"1.0\", package = \"dep_upgrade_dep\" }
+
+// src/lib.rs
+pub fn in_func(_var: u8) {}",
+        after: "\
+# Cargo.toml
+[dependencies]
+dep = { version = \"1.0\", package = \"dep_upgrade_dep\" }
+
+// src/lib.rs
+pub fn in_func(_var: dep::InFunc) {}",
+    },
+    Rule {
+        id: crate_api::diff::DEPENDENCY_AMBIGUOUS,
+        description: "\
+A public dependency is in both APIs but whether its version changed incompatibly
+couldn't be determined.
+
+This happens when neither the version requirement from `Cargo.toml` nor the
+version selected in `Cargo.lock` is known for both sides, like when diffing
+rustdoc JSON that wasn't generated through `cargo crate-api`.",
+        before: "\
+// src/lib.rs, diffed from rustdoc's JSON without `Cargo.toml`
+pub use dep::Used;",
+        after: "\
+// src/lib.rs, diffed from rustdoc's JSON without `Cargo.toml`
+pub use dep::Used;",
+    },
+    Rule {
+        id: crate_api::diff::DEPENDENCY_REQUIREMENT,
+        description: "\
+The version requirement of a public dependency changed to an incompatible version.
+
+Callers can only use the dependency's types with your API if they use the same
+major version of the dependency, so upgrading it is a breaking change.  When the
+version requirements are unknown, the versions from `Cargo.lock` are compared
+instead.",
+        before: "\
+# Cargo.toml
+[dependencies]
+dep = { path = \"../dep_old\", version = \"1.0\", package = \"dep_upgrade_dep\" }
+
+// src/lib.rs
+pub use dep::Used;",
+        after: "\
+# Cargo.toml
+[dependencies]
+dep = { path = \"../dep_new\", version = \"2.0\", package = \"dep_upgrade_dep\" }
+
+// src/lib.rs
+pub use dep::Used;",
+    },
+    Rule {
+        id: crate_api::diff::DEPENDENCY_UPGRADE_EXPOSED,
+        description: "\
+A public item exposes a type from a dependency that was upgraded to an
+incompatible version.
+
+This is reported for each item that references the dependency, so you can see
+which parts of your API callers may need to update for.  See
+`dependency-requirement` for the upgrade itself.",
+        before: "\
+# Cargo.toml
+[dependencies]
+dep = { path = \"../dep_old\", version = \"1.0\", package = \"dep_upgrade_dep\" }
+
+// src/lib.rs
+pub fn in_func(_var: dep::InFunc) {}",
+        after: "\
+# Cargo.toml
+[dependencies]
+dep = { path = \"../dep_new\", version = \"2.0\", package = \"dep_upgrade_dep\" }
+
+// src/lib.rs
+pub fn in_func(_var: dep::InFunc) {}",
+    },
+];
+
+pub fn find(name: &str) -> Option<Id> {
+    crate_api::diff::ALL_IDS
+        .iter()
+        .copied()
+        .find(|id| id.name == name)
+}
+
+pub fn render_rules(writer: &mut dyn std::io::Write) {
+    let width = crate_api::diff::ALL_IDS
+        .iter()
+        .map(|id| id.name.len())
+        .max()
+        .unwrap_or_default();
+    for id in crate_api::diff::ALL_IDS {
+        let _ = writeln!(
+            writer,
+            "{:width$}  {:9}  {:6}  {}",
+            id.name,
+            category_name(id.category),
+            severity_name(id.default_severity),
+            id.explanation,
+            width = width
+        );
+    }
+}
+
+pub fn render_explanation(writer: &mut dyn std::io::Write, id: Id) {
+    let _ = writeln!(writer, "{}: {}", id.name, id.explanation);
+    let _ = writeln!(writer);
+    let _ = writeln!(writer, "Category: {}", category_name(id.category));
+    let _ = writeln!(
+        writer,
+        "Default severity: {}",
+        severity_name(id.default_severity)
+    );
+
+    if let Some(rule) = RULES.iter().find(|rule| rule.id == id) {
+        let _ = writeln!(writer);
+        let _ = writeln!(writer, "{}", rule.description);
+        let _ = writeln!(writer);
+        let _ = writeln!(writer, "Before:");
+        let _ = writeln!(writer);
+        render_example(writer, rule.before);
+        let _ = writeln!(writer);
+        let _ = writeln!(writer, "After:");
+        let _ = writeln!(writer);
+        render_example(writer, rule.after);
+    }
+}
+
+fn render_example(writer: &mut dyn std::io::Write, example: &str) {
+    for line in example.lines() {
+        if line.is_empty() {
+            let _ = writeln!(writer);
+        } else {
+            let _ = writeln!(writer, "    {}", line);
+        }
+    }
+}
+
+fn category_name(category: crate_api::diff::Category) -> &'static str {
+    match category {
+        crate_api::diff::Category::Unknown => "unknown",
+        crate_api::diff::Category::Added => "added",
+        crate_api::diff::Category::Removed => "removed",
+        crate_api::diff::Category::Changed => "changed",
+    }
+}
+
+fn severity_name(severity: crate_api::diff::Severity) -> &'static str {
+    match severity {
+        crate_api::diff::Severity::Allow => "allow",
+        crate_api::diff::Severity::Report => "report",
+        crate_api::diff::Severity::Warn => "warn",
+    }
+}
+
+#[test]
+fn all_ids_are_explained() {
+    for id in crate_api::diff::ALL_IDS {
+        assert!(
+            RULES.iter().any(|rule| rule.id == *id),
+            "`{}` is missing from `RULES`",
+            id.name
+        );
+    }
+}
@@ -0,0 +1,156 @@
+fn main() {
+    let action = std::env::var("DIFF_REPORT");
+    let action = action.as_deref().unwrap_or("verify");
+    let action = match action {
+        "overwrite" => Action::Overwrite,
+        "ignore" => Action::Ignore,
+        "verify" => Action::Verify,
+        _ => panic!(
+            "Unrecognized action {}, expected `overwrite`, `ignore`, or `verify`",
+            action
+        ),
+    };
+
+    fs_snapshot::Harness::new(
+        "tests/diff_report",
+        move |input_path| {
+            let name = input_path
+                .strip_prefix("tests/diff_report")
+                .unwrap_or(&input_path)
+                .with_extension("")
+                .to_string_lossy()
+                .replace('\\', "/");
+            let expected = input_path.with_extension("stdout");
+            fs_snapshot::Test {
+                name,
+                kind: "".into(),
+                is_ignored: action == Action::Ignore,
+                is_bench: false,
+                data: fs_snapshot::Case {
+                    fixture: input_path,
+                    expected,
+                },
+            }
+        },
+        move |input_path| {
+            let args = std::fs::read_to_string(input_path).map_err(|e| e.to_string())?;
+            let args: Vec<_> = args.split_whitespace().collect();
+            let is_workspace = input_path.parent().and_then(|p| p.file_name())
+                == Some(std::ffi::OsStr::new("workspace"));
+            if is_workspace {
+                // Cases share the workspace's worktree and build
+                let root = WORKSPACE.lock().unwrap_or_else(|e| e.into_inner());
+                let stdout = cargo_crate_api(&root, &args)?;
+                Ok(stdout.replace(root.to_string_lossy().as_ref(), "[ROOT]"))
+            } else {
+                let fixtures =
+                    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures");
+                cargo_crate_api(&fixtures, &args)
+            }
+        },
+    )
+    .select(["*.args"])
+    .overwrite(action == Action::Overwrite)
+    .test()
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Action {
+    Overwrite,
+    Verify,
+    Ignore,
+}
+
+fn cargo_crate_api(cwd: &std::path::Path, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_cargo-crate-api"))
+        .current_dir(cwd)
+        .env_remove("CARGO_TARGET_DIR")
+        .env_remove("GITHUB_WORKSPACE")
+        .env_remove("GITHUB_STEP_SUMMARY")
+        .arg("crate-api")
+        .args(args)
+        .output()
+        .map_err(|e| e.to_string())?;
+    let stdout = String::from_utf8(output.stdout).map_err(|e| e.to_string())?;
+    if stdout.is_empty() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    Ok(stdout)
+}
+
+static WORKSPACE: once_cell::sync::Lazy<std::sync::Mutex<std::path::PathBuf>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(workspace()));
+
+/// A git repository with a workspace whose working tree upgrades a public dependency of `upgrade`
+/// and breaks `broken`
+fn workspace() -> std::path::PathBuf {
+    let root = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("diff_report");
+    let _ = std::fs::remove_dir_all(&root);
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    };
+    let dep = |version: &str| {
+        format!(
+            "[package]\nname = \"dep\"\nversion = \"{}\"\nedition = \"2021\"\n",
+            version
+        )
+    };
+    let upgrade = |dep_path: &str, version: &str| {
+        format!(
+            "[package]\nname = \"upgrade\"\nversion = \"1.0.0\"\nedition = \"2021\"\n\n[dependencies]\ndep = {{ path = \"../{}\", version = \"{}\" }}\n",
+            dep_path, version
+        )
+    };
+    let package = |name: &str| {
+        format!(
+            "[package]\nname = \"{}\"\nversion = \"1.0.0\"\nedition = \"2021\"\n",
+            name
+        )
+    };
+
+    write(
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"upgrade\", \"broken\"]\nexclude = [\"dep_old\", \"dep_new\"]\n",
+    );
+    write(".gitignore", "/target\nCargo.lock\n");
+    for (dir, version) in [("dep_old", "1.0.0"), ("dep_new", "2.0.0")] {
+        write(&format!("{}/Cargo.toml", dir), &dep(version));
+        write(
+            &format!("{}/src/lib.rs", dir),
+            "pub struct Used;\n\npub struct InFunc;\n",
+        );
+    }
+    write("upgrade/Cargo.toml", &upgrade("dep_old", "1.0"));
+    write(
+        "upgrade/src/lib.rs",
+        "pub use dep::Used;\n\npub fn in_func(_var: dep::InFunc) {}\n",
+    );
+    write("broken/Cargo.toml", &package("broken"));
+    write("broken/src/lib.rs", "pub fn broken() {}\n");
+    git(&root, &["init", "--quiet"]);
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "--quiet", "--message", "Release"]);
+
+    write("upgrade/Cargo.toml", &upgrade("dep_new", "2.0"));
+    write("broken/src/lib.rs", "pub fn broken() {\n");
+    root
+}
+
+fn git(cwd: &std::path::Path, args: &[&str]) {
+    // Pin everything the commit id depends on so it can be snapshotted
+    let status = std::process::Command::new("git")
+        .current_dir(cwd)
+        .args(["-c", "commit.gpgsign=false"])
+        .args(args)
+        .env("GIT_AUTHOR_NAME", "crate-api")
+        .env("GIT_AUTHOR_EMAIL", "crate-api@example.com")
+        .env("GIT_AUTHOR_DATE", "2022-04-01T00:00:00Z")
+        .env("GIT_COMMITTER_NAME", "crate-api")
+        .env("GIT_COMMITTER_EMAIL", "crate-api@example.com")
+        .env("GIT_COMMITTER_DATE", "2022-04-01T00:00:00Z")
+        .status()
+        .unwrap();
+    assert!(status.success(), "`git {}` failed", args.join(" "));
+}
@@ -0,0 +1,66 @@
+fn cargo_crate_api(args: &[&str], manifest_path: &std::path::Path) -> std::process::Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_cargo-crate-api"))
+        .args(["crate-api", "--diff", "--format", "json"])
+        .args(args)
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .output()
+        .unwrap()
+}
+
+/// A local copy of a published package, outside of any git repository
+fn package(version: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"cfg-if\"\nversion = \"{}\"\nedition = \"2018\"\n\n[workspace]\n",
+            version
+        ),
+    )
+    .unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/lib.rs"), "pub fn exported() {}\n").unwrap();
+    dir
+}
+
+#[test]
+fn defaults_to_registry_without_release_tags() {
+    let dir = package("1.0.1");
+    let output = cargo_crate_api(&[], &dir.path().join("Cargo.toml"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("\"cfg-if@1.0.1\""), "{}", stdout);
+}
+
+#[test]
+fn registry_version_is_respected() {
+    let dir = package("1.0.1");
+    let output = cargo_crate_api(
+        &["--registry", "cfg-if@1.0.0"],
+        &dir.path().join("Cargo.toml"),
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("\"cfg-if@1.0.0\""), "{}", stdout);
+}
+
+#[test]
+fn unpublished_package_fails() {
+    let dir = package("1.0.1");
+    let output = cargo_crate_api(
+        &["--registry", "crate-api-never-published"],
+        &dir.path().join("Cargo.toml"),
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("from the registry"), "{}", stderr);
+}
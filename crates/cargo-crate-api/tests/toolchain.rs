@@ -0,0 +1,53 @@
+/// Run `cargo crate-api` the way rustup does when the default toolchain is stable
+fn cargo_crate_api(manifest_path: &std::path::Path) -> std::process::Output {
+    let bin_dir = std::path::Path::new(env!("CARGO_BIN_EXE_cargo-crate-api"))
+        .parent()
+        .unwrap();
+    let mut paths = vec![bin_dir.to_owned()];
+    paths.extend(std::env::split_paths(
+        &std::env::var_os("PATH").unwrap_or_default(),
+    ));
+    std::process::Command::new("cargo")
+        .env("PATH", std::env::join_paths(paths).unwrap())
+        .env("RUSTUP_TOOLCHAIN", "stable")
+        .args(["crate-api", "--api", "--format", "json", "--manifest-path"])
+        .arg(manifest_path)
+        .output()
+        .unwrap()
+}
+
+fn package(files: &[(&str, &str)]) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"toolchain\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+    )
+    .unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/lib.rs"), "pub fn exported() {}\n").unwrap();
+    for (path, content) in files {
+        std::fs::write(dir.path().join(path), content).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn defaults_to_nightly_under_rustup() {
+    let dir = package(&[]);
+    let output = cargo_crate_api(&dir.path().join("Cargo.toml"));
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("toolchain::exported"));
+}
+
+#[test]
+fn toolchain_file_is_respected_under_rustup() {
+    let dir = package(&[("rust-toolchain.toml", "[toolchain]\nchannel = \"stable\"\n")]);
+    let output = cargo_crate_api(&dir.path().join("Cargo.toml"));
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not a nightly toolchain"), "{}", stderr);
+}
@@ -13,6 +13,57 @@ impl Api {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Remove paths that don't match `predicate`, unless they lead to one that does
+    ///
+    /// The root is always kept.  `PathId`s are renumbered.
+    pub fn retain_paths(&mut self, mut predicate: impl FnMut(&Path) -> bool) {
+        let mut parents: std::collections::HashMap<PathId, Vec<PathId>> = Default::default();
+        for (path_id, path) in self.paths.iter() {
+            for child_id in &path.children {
+                parents.entry(*child_id).or_default().push(path_id);
+            }
+        }
+
+        let mut keep: std::collections::HashSet<PathId> = self.root_id.into_iter().collect();
+        let mut pending: Vec<_> = self
+            .paths
+            .iter()
+            .filter(|(_, path)| predicate(path))
+            .map(|(path_id, _)| path_id)
+            .collect();
+        while let Some(path_id) = pending.pop() {
+            if keep.insert(path_id) {
+                pending.extend(parents.get(&path_id).into_iter().flatten().copied());
+            }
+        }
+
+        let mut new_ids = std::collections::HashMap::new();
+        let mut paths = Paths::new();
+        for (path_id, path) in self.paths.iter() {
+            if keep.contains(&path_id) {
+                new_ids.insert(path_id, paths.push(path.clone()));
+            }
+        }
+        for (_, path) in paths.iter_mut() {
+            path.children = path
+                .children
+                .iter()
+                .filter_map(|child_id| new_ids.get(child_id).copied())
+                .collect();
+        }
+        for (_, crate_) in self.crates.iter_mut() {
+            crate_.exposed_by = crate_
+                .exposed_by
+                .iter()
+                .filter_map(|path_id| new_ids.get(path_id).copied())
+                .collect();
+        }
+        self.root_id = self
+            .root_id
+            .and_then(|root_id| new_ids.get(&root_id).copied());
+        self.paths = paths;
+    }
 }
 
 #[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
@@ -70,6 +121,9 @@ pub struct Path {
     pub path: String,
     pub kind: PathKind,
     pub span: Option<Span>,
+    /// The item's declaration, rendered as Rust
+    #[serde(default)]
+    pub signature: Option<String>,
     pub item_id: Option<ItemId>,
     pub children: Vec<PathId>,
 }
@@ -81,6 +135,7 @@ impl Path {
             path: path.into(),
             kind,
             span: None,
+            signature: None,
             item_id: None,
             children: Vec::new(),
         }
@@ -244,6 +299,18 @@ pub struct CrateId(usize);
 pub struct Crate {
     pub name: String,
     pub version: Option<cargo_metadata::VersionReq>,
+    /// Where rustdoc links to for this crate's documentation, if known
+    #[serde(default)]
+    pub html_root_url: Option<String>,
+    /// The version selected in `Cargo.lock`, if known
+    #[serde(default)]
+    pub resolved: Option<cargo_metadata::Version>,
+    /// Names paths into this crate were qualified with, like `dep` in `dep::Item`
+    #[serde(default)]
+    pub extern_names: Vec<String>,
+    /// Public paths whose signatures reference this crate
+    #[serde(default)]
+    pub exposed_by: Vec<PathId>,
 }
 
 impl Crate {
@@ -251,6 +318,10 @@ impl Crate {
         Self {
             name: name.into(),
             version: None,
+            html_root_url: None,
+            resolved: None,
+            extern_names: Vec::new(),
+            exposed_by: Vec::new(),
         }
     }
 }
@@ -0,0 +1,329 @@
+/// The API of a package at two points in time and how they differ
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Comparison {
+    pub before: crate::Api,
+    pub after: crate::Api,
+    pub diffs: Vec<crate::diff::Diff>,
+}
+
+impl Comparison {
+    pub fn new(before: crate::Api, after: crate::Api) -> Self {
+        let mut diffs = Vec::new();
+        crate::diff::diff(&before, &after, &mut diffs);
+        Self {
+            before,
+            after,
+            diffs,
+        }
+    }
+}
+
+/// Extract and diff the APIs of a package
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComparisonBuilder {
+    rustdoc: crate::RustDocBuilder,
+    target_directory: Option<std::path::PathBuf>,
+    cache: bool,
+}
+
+impl ComparisonBuilder {
+    pub fn new() -> Self {
+        Self {
+            rustdoc: crate::RustDocBuilder::new(),
+            target_directory: None,
+            cache: false,
+        }
+    }
+
+    /// How to generate the API for each side of the comparison
+    pub fn rustdoc(mut self, rustdoc: crate::RustDocBuilder) -> Self {
+        self.rustdoc = rustdoc;
+        self
+    }
+
+    /// Where to check out git revisions, cache their APIs, and build each side
+    ///
+    /// Defaults to `crate-api` within the package's target directory.
+    pub fn target_directory(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.target_directory = Some(path.into());
+        self
+    }
+
+    /// Reuse the API extracted from a git revision
+    ///
+    /// APIs are cached in the target directory, keyed by the git tree, package, rustdoc
+    /// version, the `RustDocBuilder` settings that affect the API, and this crate's version.
+    pub fn cache(mut self, yes: bool) -> Self {
+        self.cache = yes;
+        self
+    }
+
+    /// Compare the packages at `before` and `after`
+    ///
+    /// Both sides are extracted concurrently.
+    pub fn compare_paths(
+        &self,
+        before: &std::path::Path,
+        after: &std::path::Path,
+    ) -> Result<Comparison, crate::Error> {
+        let after = self.spawn_extract(after.to_owned(), Side::After);
+        let before = self.extract(before, Side::Before);
+        let after = join(after)?;
+        Ok(Comparison::new(before?, after))
+    }
+
+    /// Compare the package at `rev` against the one at `after`
+    ///
+    /// `after` is extracted while `rev` is checked out and extracted.
+    pub fn compare_git(
+        &self,
+        rev: &str,
+        after: &std::path::Path,
+    ) -> Result<Comparison, crate::Error> {
+        let after_metadata = metadata(after)?;
+        let target_directory = self.target_directory_for(&after_metadata);
+        let after_api = self.spawn_extract(after.to_owned(), Side::After);
+        let before_api = crate::Worktree::checkout(after, rev, &target_directory)
+            .and_then(|worktree| self.extract_worktree(&worktree, &after_metadata, after));
+        let after_api = join(after_api)?;
+
+        Ok(Comparison::new(before_api?, after_api))
+    }
+
+    /// Compare the package in `worktree` against the one at `after`
+    ///
+    /// This allows checking out a revision once for every package in a workspace.
+    pub fn compare_worktree(
+        &self,
+        worktree: &crate::Worktree,
+        after: &std::path::Path,
+    ) -> Result<Comparison, crate::Error> {
+        let after_api = self.spawn_extract(after.to_owned(), Side::After);
+        let before_api =
+            metadata(after).and_then(|metadata| self.extract_worktree(worktree, &metadata, after));
+        let after_api = join(after_api)?;
+
+        Ok(Comparison::new(before_api?, after_api))
+    }
+
+    fn extract_worktree(
+        &self,
+        worktree: &crate::Worktree,
+        after_metadata: &cargo_metadata::Metadata,
+        after: &std::path::Path,
+    ) -> Result<crate::Api, crate::Error> {
+        let after_pkg = root_package(after_metadata, after)?;
+        let before = worktree.manifest_path(&after_pkg.name)?;
+
+        let cache_path = if self.cache {
+            let target_directory = self.target_directory_for(after_metadata);
+            let rustdoc_version = self.rustdoc.rustdoc_version(&before)?;
+            Some(self.cache_path(
+                &target_directory,
+                worktree.tree_id(),
+                &after_pkg.name,
+                &rustdoc_version,
+            ))
+        } else {
+            None
+        };
+        let cached = cache_path.as_deref().and_then(|cache_path| {
+            let cached = std::fs::read_to_string(cache_path).ok()?;
+            serde_json::from_str::<crate::Api>(&cached).ok()
+        });
+        let before_api = match cached {
+            Some(cached) => cached,
+            None => {
+                let before_api = self.extract(&before, Side::Before)?;
+                if let Some(cache_path) = cache_path.as_deref() {
+                    // Failing to cache shouldn't fail the comparison
+                    let _ = write_cache(cache_path, &before_api);
+                }
+                before_api
+            }
+        };
+        Ok(before_api)
+    }
+
+    /// Where the API of `pkg_name` in `tree_id` is cached
+    ///
+    /// Every input that affects the extracted API is part of the path so changing any of them
+    /// misses the cache.
+    fn cache_path(
+        &self,
+        target_directory: &std::path::Path,
+        tree_id: &str,
+        pkg_name: &str,
+        rustdoc_version: &str,
+    ) -> std::path::PathBuf {
+        target_directory
+            .join("cache")
+            .join(concat!("crate-api-", env!("CARGO_PKG_VERSION")))
+            .join(path_component(rustdoc_version))
+            .join(self.rustdoc.cache_key())
+            .join(format!("{}-{}.json", pkg_name, tree_id))
+    }
+
+    fn target_directory_for(&self, metadata: &cargo_metadata::Metadata) -> std::path::PathBuf {
+        self.target_directory
+            .clone()
+            .unwrap_or_else(|| metadata.target_directory.as_std_path().join("crate-api"))
+    }
+
+    fn spawn_extract(
+        &self,
+        manifest_path: std::path::PathBuf,
+        side: Side,
+    ) -> std::thread::JoinHandle<Result<crate::Api, crate::Error>> {
+        let builder = self.clone();
+        std::thread::spawn(move || builder.extract(&manifest_path, side))
+    }
+
+    fn extract(
+        &self,
+        manifest_path: &std::path::Path,
+        side: Side,
+    ) -> Result<crate::Api, crate::Error> {
+        let metadata = metadata(manifest_path)?;
+        let pkg = root_package(&metadata, manifest_path)?;
+        // Both sides can resolve to the same target directory, like with `CARGO_TARGET_DIR`, and
+        // would race on `doc/<crate>.json`
+        let target_directory = match self.rustdoc.target_directory.as_deref() {
+            Some(target_directory) => target_directory.join(side.as_str()),
+            None => self.target_directory_for(&metadata).join(side.as_str()),
+        };
+        let mut api = self
+            .rustdoc
+            .clone()
+            .target_directory(target_directory)
+            .label(format!("{} ({})", pkg.name, side.as_str()))
+            .into_api(pkg.manifest_path.as_std_path())?;
+        let manifest = crate::manifest::Manifest::from_metadata(&metadata, pkg);
+        manifest.into_api(&mut api);
+        Ok(api)
+    }
+}
+
+impl Default for ComparisonBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Side {
+    Before,
+    After,
+}
+
+impl Side {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Before => "before",
+            Self::After => "after",
+        }
+    }
+}
+
+fn join<T>(handle: std::thread::JoinHandle<T>) -> T {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// Make `value` safe to use as a file name, like `rustdoc_1.60.0-nightly_9ad5d82f8_2022-01-18`
+fn path_component(value: &str) -> String {
+    value
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn write_cache(cache_path: &std::path::Path, api: &crate::Api) -> Result<(), std::io::Error> {
+    if let Some(parent) = cache_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let api = serde_json::to_string(api)?;
+    std::fs::write(cache_path, api)
+}
+
+fn metadata(manifest_path: &std::path::Path) -> Result<cargo_metadata::Metadata, crate::Error> {
+    cargo_metadata::MetadataCommand::new()
+        .manifest_path(manifest_path)
+        .exec()
+        .map_err(|e| {
+            crate::Error::new(
+                crate::ErrorKind::InvalidManifest,
+                format!("Failed when reading {}: {}", manifest_path.display(), e),
+            )
+            .set_source(e)
+        })
+}
+
+fn root_package<'m>(
+    metadata: &'m cargo_metadata::Metadata,
+    manifest_path: &std::path::Path,
+) -> Result<&'m cargo_metadata::Package, crate::Error> {
+    let root_id = metadata
+        .resolve
+        .as_ref()
+        .and_then(|resolve| resolve.root.as_ref())
+        .ok_or_else(|| {
+            crate::Error::new(
+                crate::ErrorKind::VirtualManifest,
+                format!(
+                    "Expected package manifest, got virtual workspace at {}",
+                    manifest_path.display()
+                ),
+            )
+        })?;
+    let pkg = metadata
+        .packages
+        .iter()
+        .find(|p| p.id == *root_id)
+        .expect("resolved root_id to exist");
+    Ok(pkg)
+}
+
+#[test]
+fn cache_path_changes_with_inputs() {
+    let target_directory = std::path::Path::new("target");
+    let rustdoc_version = "rustdoc 1.60.0-nightly (9ad5d82f8 2022-01-18)";
+    let builder = ComparisonBuilder::new();
+    let base = builder.cache_path(target_directory, "abc123", "foo", rustdoc_version);
+    assert_eq!(
+        base,
+        target_directory
+            .join("cache")
+            .join(concat!("crate-api-", env!("CARGO_PKG_VERSION")))
+            .join("rustdoc_1.60.0-nightly_9ad5d82f8_2022-01-18")
+            .join("no-deps")
+            .join("foo-abc123.json")
+    );
+
+    let changed = [
+        builder.cache_path(target_directory, "def456", "foo", rustdoc_version),
+        builder.cache_path(target_directory, "abc123", "bar", rustdoc_version),
+        builder.cache_path(
+            target_directory,
+            "abc123",
+            "foo",
+            "rustdoc 1.61.0-nightly (68369a041 2022-02-22)",
+        ),
+        ComparisonBuilder::new()
+            .rustdoc(crate::RustDocBuilder::new().deps(true))
+            .cache_path(target_directory, "abc123", "foo", rustdoc_version),
+    ];
+    for changed in changed {
+        assert_ne!(base, changed);
+    }
+
+    // Settings that don't affect the API share the cache
+    let unaffected = ComparisonBuilder::new()
+        .rustdoc(crate::RustDocBuilder::new().silence(true).color(false))
+        .cache_path(target_directory, "abc123", "foo", rustdoc_version);
+    assert_eq!(base, unaffected);
+}
@@ -19,6 +19,61 @@ pub struct Diff {
     pub id: Id,
     pub before: Option<Location>,
     pub after: Option<Location>,
+    /// What changed, so it can be reported without looking up the `Location`s
+    pub details: Option<Details>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "kind")]
+#[non_exhaustive]
+pub enum Details {
+    /// A public dependency and the public paths that expose it
+    Dependency {
+        name: String,
+        exposed_by: Vec<String>,
+    },
+    /// The versions of a public dependency
+    DependencyVersion {
+        name: String,
+        before: DependencyVersion,
+        after: DependencyVersion,
+    },
+    /// A public path exposing a dependency that changed versions
+    ExposedDependency {
+        path: String,
+        name: String,
+        before: DependencyVersion,
+        after: DependencyVersion,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct DependencyVersion {
+    pub requirement: Option<cargo_metadata::VersionReq>,
+    pub resolved: Option<cargo_metadata::Version>,
+}
+
+impl DependencyVersion {
+    fn new(crate_: &crate::Crate) -> Self {
+        Self {
+            requirement: crate_.version.clone(),
+            resolved: crate_.resolved.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for DependencyVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.requirement.as_ref(), self.resolved.as_ref()) {
+            (Some(requirement), Some(resolved)) => write!(f, "{} ({})", requirement, resolved),
+            (Some(requirement), None) => write!(f, "{}", requirement),
+            (None, Some(resolved)) => write!(f, "{}", resolved),
+            (None, None) => write!(f, "unknown"),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
@@ -30,7 +85,9 @@ pub enum Category {
     Changed,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum Severity {
     Allow,
@@ -56,6 +113,7 @@ pub const ALL_IDS: &[Id] = &[
     DEPENDENCY_ADDED,
     DEPENDENCY_AMBIGUOUS,
     DEPENDENCY_REQUIREMENT,
+    DEPENDENCY_UPGRADE_EXPOSED,
 ];
 
 pub const DEPENDENCY_REMOVED: Id = Id {
@@ -68,7 +126,7 @@ pub const DEPENDENCY_REMOVED: Id = Id {
 
 pub const DEPENDENCY_ADDED: Id = Id {
     name: "dependency-added",
-    explanation: "Public dependency removed because of an API change",
+    explanation: "Public dependency added because of an API change",
     category: Category::Added,
     // In case people weren't aware they added a dependency to their public API
     default_severity: Severity::Report,
@@ -89,6 +147,13 @@ pub const DEPENDENCY_REQUIREMENT: Id = Id {
     default_severity: Severity::Warn,
 };
 
+pub const DEPENDENCY_UPGRADE_EXPOSED: Id = Id {
+    name: "dependency-upgrade-exposed",
+    explanation: "Exposes types from a dependency that was upgraded to an incompatible version",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
 pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
     let before_by_name: HashMap<_, _> = before
         .crates
@@ -106,6 +171,7 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
 
     for removed_name in before_names.difference(&after_names) {
         let before_crate_id = *before_by_name.get(*removed_name).unwrap();
+        let before_crate = before.crates.get(before_crate_id).unwrap();
         changes.push(Diff {
             severity: DEPENDENCY_REMOVED.default_severity,
             id: DEPENDENCY_REMOVED,
@@ -114,11 +180,16 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
                 ..Default::default()
             }),
             after: None,
+            details: Some(Details::Dependency {
+                name: before_crate.name.clone(),
+                exposed_by: exposed_by(before, before_crate),
+            }),
         });
     }
 
     for added_name in after_names.difference(&before_names) {
         let after_crate_id = *after_by_name.get(*added_name).unwrap();
+        let after_crate = after.crates.get(after_crate_id).unwrap();
         changes.push(Diff {
             severity: DEPENDENCY_ADDED.default_severity,
             id: DEPENDENCY_ADDED,
@@ -127,6 +198,10 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
                 crate_id: Some(after_crate_id),
                 ..Default::default()
             }),
+            details: Some(Details::Dependency {
+                name: after_crate.name.clone(),
+                exposed_by: exposed_by(after, after_crate),
+            }),
         });
     }
 
@@ -136,40 +211,85 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
         let after_crate_id = *after_by_name.get(*common_name).unwrap();
         let after_crate = after.crates.get(after_crate_id).unwrap();
 
-        if before_crate.version.is_none() || after_crate.version.is_none() {
-            changes.push(Diff {
-                severity: DEPENDENCY_AMBIGUOUS.default_severity,
-                id: DEPENDENCY_AMBIGUOUS,
-                before: Some(Location {
-                    crate_id: Some(before_crate_id),
-                    ..Default::default()
-                }),
-                after: Some(Location {
-                    crate_id: Some(after_crate_id),
-                    ..Default::default()
-                }),
-            });
-        } else if before_crate.version == after_crate.version {
-        } else {
-            let (before_lower, before_upper) = breaking(before_crate.version.as_ref().unwrap());
-            let before_lower = before_lower.unwrap_or((0, 0, 0));
-            let before_upper = before_upper.unwrap_or((u64::MAX, u64::MAX, u64::MAX));
-
-            let (after_lower, after_upper) = breaking(after_crate.version.as_ref().unwrap());
-            let after_lower = after_lower.unwrap_or((0, 0, 0));
-            let after_upper = after_upper.unwrap_or((u64::MAX, u64::MAX, u64::MAX));
-
-            if before_lower < after_lower || after_upper < before_upper {
+        let requirement_breaking =
+            match (before_crate.version.as_ref(), after_crate.version.as_ref()) {
+                (Some(before_version), Some(after_version)) => {
+                    Some(breaking_requirement(before_version, after_version))
+                }
+                _ => None,
+            };
+        let resolved_breaking = match (
+            before_crate.resolved.as_ref(),
+            after_crate.resolved.as_ref(),
+        ) {
+            (Some(before_resolved), Some(after_resolved)) => {
+                Some(breaking_version(before_resolved) != breaking_version(after_resolved))
+            }
+            _ => None,
+        };
+
+        let before_location = Location {
+            crate_id: Some(before_crate_id),
+            ..Default::default()
+        };
+        let after_location = Location {
+            crate_id: Some(after_crate_id),
+            ..Default::default()
+        };
+        let before_version = DependencyVersion::new(before_crate);
+        let after_version = DependencyVersion::new(after_crate);
+        let details = Details::DependencyVersion {
+            name: after_crate.name.clone(),
+            before: before_version.clone(),
+            after: after_version.clone(),
+        };
+        // Fallback to `Cargo.lock` when the version requirements can't tell us
+        match requirement_breaking.or(resolved_breaking) {
+            Some(true) => {
                 changes.push(Diff {
                     severity: DEPENDENCY_REQUIREMENT.default_severity,
                     id: DEPENDENCY_REQUIREMENT,
+                    before: Some(before_location),
+                    after: Some(after_location),
+                    details: Some(details),
+                });
+            }
+            Some(false) => {}
+            None => {
+                changes.push(Diff {
+                    severity: DEPENDENCY_AMBIGUOUS.default_severity,
+                    id: DEPENDENCY_AMBIGUOUS,
+                    before: Some(before_location),
+                    after: Some(after_location),
+                    details: Some(details),
+                });
+            }
+        }
+
+        // Prefer `Cargo.lock` as that is what the public types were actually generated from
+        if resolved_breaking.or(requirement_breaking) == Some(true) {
+            for after_path_id in after_crate.exposed_by.iter().copied() {
+                let after_path = after.paths.get(after_path_id).unwrap();
+                let before_path_id =
+                    before_crate.exposed_by.iter().copied().find(|path_id| {
+                        before.paths.get(*path_id).unwrap().path == after_path.path
+                    });
+                changes.push(Diff {
+                    severity: DEPENDENCY_UPGRADE_EXPOSED.default_severity,
+                    id: DEPENDENCY_UPGRADE_EXPOSED,
                     before: Some(Location {
-                        crate_id: Some(before_crate_id),
-                        ..Default::default()
+                        path_id: before_path_id,
+                        ..before_location
                     }),
                     after: Some(Location {
-                        crate_id: Some(after_crate_id),
-                        ..Default::default()
+                        path_id: Some(after_path_id),
+                        ..after_location
+                    }),
+                    details: Some(Details::ExposedDependency {
+                        path: after_path.path.clone(),
+                        name: after_crate.name.clone(),
+                        before: before_version.clone(),
+                        after: after_version.clone(),
                     }),
                 });
             }
@@ -177,6 +297,42 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
     }
 }
 
+fn exposed_by(api: &crate::Api, crate_: &crate::Crate) -> Vec<String> {
+    let mut paths: Vec<_> = crate_
+        .exposed_by
+        .iter()
+        .map(|path_id| api.paths.get(*path_id).unwrap().path.clone())
+        .collect();
+    paths.sort_unstable();
+    paths
+}
+
+fn breaking_requirement(before: &semver::VersionReq, after: &semver::VersionReq) -> bool {
+    if before == after {
+        return false;
+    }
+
+    let (before_lower, before_upper) = breaking(before);
+    let before_lower = before_lower.unwrap_or((0, 0, 0));
+    let before_upper = before_upper.unwrap_or((u64::MAX, u64::MAX, u64::MAX));
+
+    let (after_lower, after_upper) = breaking(after);
+    let after_lower = after_lower.unwrap_or((0, 0, 0));
+    let after_upper = after_upper.unwrap_or((u64::MAX, u64::MAX, u64::MAX));
+
+    before_lower < after_lower || after_upper < before_upper
+}
+
+fn breaking_version(version: &semver::Version) -> VersionParts {
+    if 1 <= version.major {
+        (version.major, 0, 0)
+    } else if 1 <= version.minor {
+        (0, version.minor, 0)
+    } else {
+        (0, 0, version.patch)
+    }
+}
+
 fn breaking(version: &semver::VersionReq) -> VersionRange {
     if *version == semver::VersionReq::STAR {
         return (None, None);
@@ -32,8 +32,9 @@ impl std::fmt::Display for Error {
 
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // Deref past the `Arc` so the source can be downcast to its concrete type
         self.source
-            .as_ref()
+            .as_deref()
             .map(|s| s as &(dyn std::error::Error + 'static))
     }
 }
@@ -42,5 +43,56 @@ impl std::error::Error for Error {
 #[non_exhaustive]
 pub enum ErrorKind {
     ApiParse,
+    /// rustdoc's JSON `format_version` is outside of `rustdoc::SUPPORTED_FORMAT_VERSIONS`
+    UnsupportedFormatVersion,
+    /// The selected toolchain is missing or can't generate rustdoc JSON
+    Toolchain,
+    /// `Cargo.toml` couldn't be read or is invalid
+    InvalidManifest,
+    /// A package was expected but `Cargo.toml` is a virtual workspace
+    VirtualManifest,
+    /// `cargo doc` failed from the package not compiling
+    ///
+    /// The source is a [`CommandError`] with cargo's exit status and stderr.
+    CompileError,
+    /// `cargo doc` succeeded but rustdoc's JSON wasn't where it was expected
+    MissingOutput,
+    /// A git revision couldn't be resolved or checked out
+    Git,
+    /// A package version couldn't be found in or downloaded from the registry
+    Registry,
     Unknown,
 }
+
+/// A command exited unsuccessfully
+#[derive(Clone, Debug)]
+pub struct CommandError {
+    status: std::process::ExitStatus,
+    stderr: String,
+}
+
+impl CommandError {
+    pub(crate) fn new(status: std::process::ExitStatus, stderr: impl Into<String>) -> Self {
+        Self {
+            status,
+            stderr: stderr.into(),
+        }
+    }
+
+    pub fn status(&self) -> std::process::ExitStatus {
+        self.status
+    }
+
+    /// What the command wrote to stderr
+    pub fn stderr(&self) -> &str {
+        &self.stderr
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.status)
+    }
+}
+
+impl std::error::Error for CommandError {}
@@ -1,10 +1,16 @@
 mod api;
+mod compare;
 mod error;
+mod registry;
+mod worktree;
 
 pub mod diff;
 pub mod manifest;
 pub mod rustdoc;
 
 pub use api::*;
+pub use compare::*;
 pub use error::*;
+pub use registry::RegistryPackage;
 pub use rustdoc::RustDocBuilder;
+pub use worktree::Worktree;
@@ -19,17 +19,55 @@ impl Manifest {
                 .or_insert_with(Vec::new)
                 .push(id);
         }
+        // Multiple versions of a crate can be public, so first match dependencies by the name
+        // the source referred to them by, which `Cargo.lock` resolved to a specific version
+        let mut unmatched = Vec::new();
+        let mut matched = Vec::new();
         for dependency in self.dependencies {
-            if let Some(crate_ids) = crate_ids.get(&dependency.name) {
-                match crate_ids.len() {
-                    0 => unreachable!("Vec should only have 1+ entries"),
-                    1 => {
-                        api.crates.get_mut(crate_ids[0]).unwrap().version =
-                            Some(dependency.version);
-                    }
-                    // Can't figure out which to map it to, so ignore it
-                    _ => {}
+            let extern_name = dependency.extern_name();
+            let candidates: Vec<_> = crate_ids
+                .get(&dependency.lib_name())
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|crate_id| {
+                    let crate_ = api.crates.get(*crate_id).unwrap();
+                    crate_.extern_names.contains(&extern_name)
+                })
+                .collect();
+            if candidates.len() == 1 {
+                matched.push(candidates[0]);
+                let crate_ = api.crates.get_mut(candidates[0]).unwrap();
+                crate_.version = Some(dependency.version);
+                crate_.resolved = dependency.resolved;
+            } else {
+                unmatched.push(dependency);
+            }
+        }
+
+        // Otherwise, fall back to ruling out the crates that were matched or whose documentation
+        // is for a different version
+        for dependency in unmatched {
+            let candidates: Vec<_> = crate_ids
+                .get(&dependency.lib_name())
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|crate_id| !matched.contains(crate_id))
+                .filter(|crate_id| {
+                    let crate_ = api.crates.get(*crate_id).unwrap();
+                    dependency.is_compatible(crate_)
+                })
+                .collect();
+            match candidates.len() {
+                0 => {}
+                1 => {
+                    let crate_ = api.crates.get_mut(candidates[0]).unwrap();
+                    crate_.version = Some(dependency.version);
+                    crate_.resolved = dependency.resolved;
                 }
+                // Can't figure out which to map it to, so ignore it
+                _ => {}
             }
         }
 
@@ -41,6 +79,59 @@ impl Manifest {
     }
 }
 
+impl Manifest {
+    /// Create a `Manifest` for `pkg`, including the versions `metadata` resolved its dependencies to
+    pub fn from_metadata(
+        metadata: &cargo_metadata::Metadata,
+        pkg: &cargo_metadata::Package,
+    ) -> Self {
+        let mut manifest = Self::from(pkg);
+
+        let node = metadata
+            .resolve
+            .as_ref()
+            .and_then(|resolve| resolve.nodes.iter().find(|node| node.id == pkg.id));
+        if let Some(node) = node {
+            for node_dep in &node.deps {
+                let resolved = match metadata.packages.iter().find(|p| p.id == node_dep.pkg) {
+                    Some(resolved) => resolved,
+                    None => continue,
+                };
+                let lib_name = resolved
+                    .targets
+                    .iter()
+                    .find(|t| {
+                        t.kind
+                            .iter()
+                            .any(|k| k.ends_with("lib") || k == "proc-macro")
+                    })
+                    .map(|t| t.name.replace('-', "_"))
+                    .unwrap_or_else(|| resolved.name.replace('-', "_"));
+                // `node_dep.name` is the name the dependency is referred to by in the source, so
+                // a renamed and non-renamed dependency on the same package can be told apart
+                for dependency in manifest
+                    .dependencies
+                    .iter_mut()
+                    .filter(|d| d.name == resolved.name)
+                    .filter(|d| {
+                        let extern_name = d
+                            .rename
+                            .as_deref()
+                            .map(|rename| rename.replace('-', "_"))
+                            .unwrap_or_else(|| lib_name.clone());
+                        extern_name == node_dep.name
+                    })
+                {
+                    dependency.resolved = Some(resolved.version.clone());
+                    dependency.lib_name = Some(lib_name.clone());
+                }
+            }
+        }
+
+        manifest
+    }
+}
+
 impl<'p> From<&'p cargo_metadata::Package> for Manifest {
     fn from(pkg: &'p cargo_metadata::Package) -> Self {
         let mut features: HashMap<_, _> = pkg
@@ -117,6 +208,10 @@ pub struct Dependency {
     pub name: String,
     pub version: cargo_metadata::VersionReq,
     pub rename: Option<String>,
+    /// The version selected in `Cargo.lock`, if known
+    pub resolved: Option<cargo_metadata::Version>,
+    /// The crate name rustdoc will report, if known
+    pub lib_name: Option<String>,
 }
 
 impl Dependency {
@@ -125,8 +220,41 @@ impl Dependency {
             name: dep.name.clone(),
             version: dep.req.clone(),
             rename: dep.rename.clone(),
+            resolved: None,
+            lib_name: None,
         }
     }
+
+    /// The crate name rustdoc will report
+    fn lib_name(&self) -> String {
+        self.lib_name
+            .clone()
+            .unwrap_or_else(|| self.name.replace('-', "_"))
+    }
+
+    /// The name the source refers to the dependency by
+    fn extern_name(&self) -> String {
+        self.rename
+            .as_deref()
+            .map(|rename| rename.replace('-', "_"))
+            .unwrap_or_else(|| self.lib_name())
+    }
+
+    fn is_compatible(&self, crate_: &crate::Crate) -> bool {
+        let documented = crate_.html_root_url.as_deref().and_then(version_from_url);
+        match (documented, self.resolved.as_ref()) {
+            (Some(documented), Some(resolved)) => documented == *resolved,
+            (Some(documented), None) => self.version.matches(&documented),
+            // Not enough information to rule it out
+            (None, _) => true,
+        }
+    }
+}
+
+/// Extract the version from a docs.rs-style `html_root_url` (e.g. `https://docs.rs/adler/0.2.3/`)
+fn version_from_url(url: &str) -> Option<cargo_metadata::Version> {
+    url.rsplit('/')
+        .find_map(|segment| cargo_metadata::Version::parse(segment).ok())
 }
 
 impl From<Dependency> for crate::OptionalDependency {
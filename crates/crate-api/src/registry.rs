@@ -0,0 +1,123 @@
+/// A published version of a package, downloaded from the registry
+///
+/// The package is copied out of cargo's registry cache so it builds as its own workspace.  Releases
+/// are immutable so the copy is reused between runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistryPackage {
+    path: std::path::PathBuf,
+    name: String,
+    version: semver::Version,
+}
+
+impl RegistryPackage {
+    /// Download the highest version of `name` matching `version_req` within `target_directory`
+    pub fn download(
+        name: &str,
+        version_req: &semver::VersionReq,
+        target_directory: &std::path::Path,
+    ) -> Result<Self, crate::Error> {
+        let registry_err = |context: String| {
+            crate::Error::new(
+                crate::ErrorKind::Registry,
+                format!(
+                    "Failed to download {} {} from the registry: {}",
+                    name, version_req, context
+                ),
+            )
+        };
+        let io_err = |path: &std::path::Path, e: std::io::Error| {
+            registry_err(format!("{}: {}", path.display(), e)).set_source(e)
+        };
+
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let registry_directory = cwd.join(target_directory).join("registry");
+
+        // Let cargo resolve and download the version through a package depending on it
+        let resolver = registry_directory.join("resolve").join(name);
+        std::fs::create_dir_all(resolver.join("src")).map_err(|e| io_err(&resolver, e))?;
+        let manifest = format!(
+            r#"[package]
+name = "crate-api-resolve"
+version = "0.0.0"
+
+[dependencies]
+{} = "{}"
+
+[workspace]
+"#,
+            name, version_req
+        );
+        let resolver_manifest = resolver.join("Cargo.toml");
+        std::fs::write(&resolver_manifest, manifest).map_err(|e| io_err(&resolver, e))?;
+        std::fs::write(resolver.join("src/lib.rs"), "").map_err(|e| io_err(&resolver, e))?;
+        // Resolve from scratch to pick up newly published versions
+        let _ = std::fs::remove_file(resolver.join("Cargo.lock"));
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(&resolver_manifest)
+            .exec()
+            .map_err(|e| registry_err(e.to_string()).set_source(e))?;
+        let package = metadata
+            .packages
+            .iter()
+            .find(|p| p.name == name && p.source.is_some())
+            .ok_or_else(|| registry_err("cargo did not resolve it".to_owned()))?;
+        let source = package
+            .manifest_path
+            .parent()
+            .expect("manifests are in a directory")
+            .as_std_path();
+
+        let path = registry_directory.join(format!("{}-{}", name, package.version));
+        if !path.join("Cargo.toml").is_file() {
+            // Copy aside and rename so an interrupted copy isn't mistaken for a complete one
+            let partial = registry_directory.join(format!("{}-{}.partial", name, package.version));
+            let _ = std::fs::remove_dir_all(&partial);
+            copy_dir(source, &partial).map_err(|e| io_err(source, e))?;
+            // Keep the package out of any workspace `target_directory` is in
+            let manifest_path = partial.join("Cargo.toml");
+            let mut manifest =
+                std::fs::read_to_string(&manifest_path).map_err(|e| io_err(&manifest_path, e))?;
+            manifest.push_str("\n[workspace]\n");
+            std::fs::write(&manifest_path, manifest).map_err(|e| io_err(&manifest_path, e))?;
+            std::fs::rename(&partial, &path).map_err(|e| io_err(&path, e))?;
+        }
+
+        Ok(Self {
+            path,
+            name: name.to_owned(),
+            version: package.version.clone(),
+        })
+    }
+
+    /// Root of the downloaded package
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The version `version_req` resolved to
+    pub fn version(&self) -> &semver::Version {
+        &self.version
+    }
+
+    pub fn manifest_path(&self) -> std::path::PathBuf {
+        self.path.join("Cargo.toml")
+    }
+}
+
+fn copy_dir(source: &std::path::Path, dest: &std::path::Path) -> Result<(), std::io::Error> {
+    std::fs::create_dir_all(dest)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let dest = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            std::fs::copy(entry.path(), dest)?;
+        }
+    }
+    Ok(())
+}
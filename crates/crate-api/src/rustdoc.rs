@@ -1,12 +1,22 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 
+mod format;
+mod signature;
+
+pub use format::SUPPORTED_FORMAT_VERSIONS;
+pub use signature::render_declaration;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RustDocBuilder {
     deps: bool,
-    target_directory: Option<std::path::PathBuf>,
+    pub(crate) target_directory: Option<std::path::PathBuf>,
     silence: bool,
     color: Option<bool>,
+    toolchain: Option<String>,
+    label: Option<String>,
 }
 
 impl RustDocBuilder {
@@ -16,6 +26,8 @@ impl RustDocBuilder {
             target_directory: None,
             silence: false,
             color: None,
+            toolchain: None,
+            label: None,
         }
     }
 
@@ -52,38 +64,102 @@ impl RustDocBuilder {
         self
     }
 
+    /// Toolchain to run rustdoc with, like `nightly-2022-03-01`
+    ///
+    /// When unset, `rust-toolchain.toml` is respected, falling back to `nightly`.  `RUSTUP_TOOLCHAIN`
+    /// is ignored as rustup sets it for every cargo subcommand, whatever the user asked for.
+    pub fn toolchain(mut self, toolchain: impl Into<Option<String>>) -> Self {
+        self.toolchain = toolchain.into();
+        self
+    }
+
+    /// Prefix each line of progress with `[label]`
+    ///
+    /// This keeps the output of concurrent runs readable.
+    pub fn label(mut self, label: impl Into<Option<String>>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// The `rustdoc --version` of the toolchain that will be used for `manifest_path`
+    pub fn rustdoc_version(&self, manifest_path: &std::path::Path) -> Result<String, crate::Error> {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let manifest_path = cwd.join(manifest_path);
+        let manifest_dir = manifest_path
+            .parent()
+            .expect("manifest path always has a parent");
+        let toolchain = self.resolve_toolchain(manifest_dir);
+        check_toolchain(toolchain.as_deref(), manifest_dir)
+    }
+
     pub fn dump_raw(self, manifest_path: &std::path::Path) -> Result<String, crate::Error> {
+        // Run from the package so rustup finds any `rust-toolchain.toml`, making paths absolute
+        // so they are unaffected
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let manifest_path = cwd.join(manifest_path);
+        let manifest_path = manifest_path.as_path();
+        let manifest_dir = manifest_path
+            .parent()
+            .expect("manifest path always has a parent");
+        let toolchain = self.resolve_toolchain(manifest_dir);
+        check_toolchain(toolchain.as_deref(), manifest_dir)?;
+
         let manifest = std::fs::read_to_string(manifest_path).map_err(|e| {
             crate::Error::new(
-                crate::ErrorKind::ApiParse,
+                crate::ErrorKind::InvalidManifest,
                 format!("Failed when reading {}: {}", manifest_path.display(), e),
             )
+            .set_source(e)
         })?;
         let manifest: toml_edit::Document = manifest.parse().map_err(|e| {
             crate::Error::new(
-                crate::ErrorKind::ApiParse,
+                crate::ErrorKind::InvalidManifest,
                 format!("Failed to parse {}: {}", manifest_path.display(), e),
             )
+            .set_source(e)
         })?;
-        let crate_name = manifest["package"]["name"].as_str().ok_or_else(|| {
+        if manifest.get("package").is_none() && manifest.get("workspace").is_some() {
+            return Err(crate::Error::new(
+                crate::ErrorKind::VirtualManifest,
+                format!(
+                    "Expected package manifest, got virtual workspace at {}",
+                    manifest_path.display()
+                ),
+            ));
+        }
+        let package_name = manifest["package"]["name"].as_str().ok_or_else(|| {
             crate::Error::new(
-                crate::ErrorKind::ApiParse,
+                crate::ErrorKind::InvalidManifest,
                 format!(
                     "Failed to parse {}: invalid package.name",
                     manifest_path.display()
                 ),
             )
         })?;
+        // rustdoc names its output after the library, like `cfg_if` for `cfg-if`
+        let crate_name = manifest
+            .get("lib")
+            .and_then(|lib| lib.get("name"))
+            .and_then(|name| name.as_str())
+            .unwrap_or(package_name)
+            .replace('-', "_");
 
         let manifest_target_directory;
         let target_dir = if let Some(target_dir) = self.target_directory.as_deref() {
-            target_dir
+            manifest_target_directory = cwd.join(target_dir);
+            manifest_target_directory.as_path()
         } else {
             let metadata = cargo_metadata::MetadataCommand::new()
                 .manifest_path(manifest_path)
                 .no_deps()
                 .exec()
-                .map_err(|e| crate::Error::new(crate::ErrorKind::ApiParse, e))?;
+                .map_err(|e| {
+                    crate::Error::new(
+                        crate::ErrorKind::InvalidManifest,
+                        format!("Failed when reading {}: {}", manifest_path.display(), e),
+                    )
+                    .set_source(e)
+                })?;
             manifest_target_directory = metadata
                 .target_directory
                 .as_path()
@@ -93,21 +169,15 @@ impl RustDocBuilder {
             manifest_target_directory.as_path()
         };
 
-        let stderr = if self.silence {
-            std::process::Stdio::piped()
-        } else {
-            // Print cargo doc progress
-            std::process::Stdio::inherit()
-        };
-
         let mut cmd = std::process::Command::new("cargo");
+        select_toolchain(&mut cmd, toolchain.as_deref());
         cmd.env(
             "RUSTDOCFLAGS",
             "-Z unstable-options --document-hidden-items --output-format=json",
         )
+        .current_dir(manifest_dir)
         .stdout(std::process::Stdio::null()) // Don't pollute cargo api output
-        .stderr(stderr)
-        .args(["+nightly", "doc", "--all-features"])
+        .args(["doc", "--all-features"])
         .arg("--manifest-path")
         .arg(manifest_path)
         .arg("--target-dir")
@@ -123,15 +193,28 @@ impl RustDocBuilder {
             }
         }
 
-        let output = cmd
-            .output()
-            .map_err(|e| crate::Error::new(crate::ErrorKind::ApiParse, e))?;
+        // Always capture stderr so failures can be classified, printing cargo doc's progress
+        // as it goes unless silenced
+        let output = if self.silence {
+            cmd.output()
+        } else {
+            output_forwarding_stderr(&mut cmd, self.label.as_deref())
+        }
+        .map_err(|e| {
+            crate::Error::new(
+                crate::ErrorKind::Toolchain,
+                format!("Failed when running cargo-doc: {}", e),
+            )
+            .set_source(e)
+        })?;
         if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let kind = classify_cargo_error(&stderr);
             let message = if self.silence {
                 format!(
                     "Failed when running cargo-doc on {}: {}",
                     manifest_path.display(),
-                    String::from_utf8_lossy(&output.stderr)
+                    stderr
                 )
             } else {
                 format!(
@@ -139,15 +222,17 @@ impl RustDocBuilder {
                     manifest_path.display(),
                 )
             };
-            return Err(crate::Error::new(crate::ErrorKind::ApiParse, message));
+            return Err(crate::Error::new(kind, message)
+                .set_source(crate::CommandError::new(output.status, stderr)));
         }
 
         let json_path = target_dir.join(format!("doc/{}.json", crate_name));
         std::fs::read_to_string(&json_path).map_err(|e| {
             crate::Error::new(
-                crate::ErrorKind::ApiParse,
+                crate::ErrorKind::MissingOutput,
                 format!("Failed when loading {}: {}", json_path.display(), e),
             )
+            .set_source(e)
         })
     }
 
@@ -155,6 +240,197 @@ impl RustDocBuilder {
         let raw = self.dump_raw(manifest_path)?;
         parse_raw(&raw, manifest_path)
     }
+
+    /// Identify the settings that affect the extracted API, for caching it
+    pub(crate) fn cache_key(&self) -> &'static str {
+        if self.deps {
+            "deps"
+        } else {
+            "no-deps"
+        }
+    }
+
+    /// `None` means to defer to rustup's `rust-toolchain.toml` lookup
+    fn resolve_toolchain(&self, manifest_dir: &std::path::Path) -> Option<String> {
+        if let Some(toolchain) = self.toolchain.as_deref() {
+            return Some(toolchain.to_owned());
+        }
+        let has_toolchain_file = manifest_dir.ancestors().any(|dir| {
+            dir.join("rust-toolchain.toml").exists() || dir.join("rust-toolchain").exists()
+        });
+        if has_toolchain_file {
+            return None;
+        }
+        Some("nightly".to_owned())
+    }
+}
+
+/// Run `cmd`, capturing its stderr while forwarding it a line at a time
+///
+/// With a `label`, each line is prefixed so concurrent runs can be told apart.
+fn output_forwarding_stderr(
+    cmd: &mut std::process::Command,
+    label: Option<&str>,
+) -> Result<std::process::Output, std::io::Error> {
+    use std::io::BufRead;
+
+    let mut child = cmd.stderr(std::process::Stdio::piped()).spawn()?;
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let mut captured = Vec::new();
+    for line in std::io::BufReader::new(stderr).lines() {
+        let line = line?;
+        match label {
+            Some(label) => eprintln!("[{}] {}", label, line),
+            None => eprintln!("{}", line),
+        }
+        captured.extend_from_slice(line.as_bytes());
+        captured.push(b'\n');
+    }
+    let mut output = child.wait_with_output()?;
+    output.stderr = captured;
+    Ok(output)
+}
+
+/// Tell apart cargo failing to compile the package from failing to load it or its dependencies,
+/// leaving anything else as `Unknown`
+fn classify_cargo_error(stderr: &str) -> crate::ErrorKind {
+    const COMPILE_ERRORS: &[&str] = &["error: could not document", "error: could not compile"];
+    const MANIFEST_ERRORS: &[&str] = &[
+        "failed to parse manifest",
+        "failed to load manifest",
+        "failed to load source for dependency",
+    ];
+    if COMPILE_ERRORS.iter().any(|e| stderr.contains(e)) {
+        crate::ErrorKind::CompileError
+    } else if MANIFEST_ERRORS.iter().any(|e| stderr.contains(e)) {
+        crate::ErrorKind::InvalidManifest
+    } else {
+        crate::ErrorKind::Unknown
+    }
+}
+
+/// The results of `check_toolchain` for this run, keyed by `toolchain_key`
+static CHECKED_TOOLCHAINS: once_cell::sync::Lazy<
+    std::sync::Mutex<HashMap<String, Result<String, crate::Error>>>,
+> = once_cell::sync::Lazy::new(Default::default);
+
+/// Verify the toolchain's rustdoc can generate JSON, which requires nightly
+///
+/// Each toolchain is only checked once per run.
+fn check_toolchain(
+    toolchain: Option<&str>,
+    manifest_dir: &std::path::Path,
+) -> Result<String, crate::Error> {
+    let key = toolchain_key(toolchain, manifest_dir);
+    if let Some(checked) = CHECKED_TOOLCHAINS.lock().unwrap().get(&key) {
+        return checked.clone();
+    }
+    let checked = _check_toolchain(toolchain, manifest_dir);
+    CHECKED_TOOLCHAINS
+        .lock()
+        .unwrap()
+        .insert(key, checked.clone());
+    checked
+}
+
+/// Run `cmd`, a rustup proxy, with `toolchain` or else the `rust-toolchain.toml` it finds
+fn select_toolchain(cmd: &mut std::process::Command, toolchain: Option<&str>) {
+    match toolchain {
+        Some(toolchain) => {
+            cmd.arg(format!("+{}", toolchain));
+        }
+        None => {
+            // rustup sets this for the cargo running us and it would take precedence over
+            // `rust-toolchain.toml`
+            cmd.env_remove("RUSTUP_TOOLCHAIN");
+        }
+    }
+}
+
+/// Identify the toolchain rustup will select
+fn toolchain_key(toolchain: Option<&str>, manifest_dir: &std::path::Path) -> String {
+    if let Some(toolchain) = toolchain {
+        return toolchain.to_owned();
+    }
+    manifest_dir
+        .ancestors()
+        .find(|dir| dir.join("rust-toolchain.toml").exists() || dir.join("rust-toolchain").exists())
+        .unwrap_or(manifest_dir)
+        .display()
+        .to_string()
+}
+
+fn _check_toolchain(
+    toolchain: Option<&str>,
+    manifest_dir: &std::path::Path,
+) -> Result<String, crate::Error> {
+    let toolchain_name = toolchain.unwrap_or("the default toolchain");
+
+    let mut cmd = std::process::Command::new("rustdoc");
+    select_toolchain(&mut cmd, toolchain);
+    cmd.arg("--version").current_dir(manifest_dir);
+    let output = cmd.output().map_err(|e| {
+        crate::Error::new(
+            crate::ErrorKind::Toolchain,
+            format!("Failed to run rustdoc for {}: {}", toolchain_name, e),
+        )
+        .set_source(e)
+    })?;
+    if !output.status.success() {
+        return Err(crate::Error::new(
+            crate::ErrorKind::Toolchain,
+            format!(
+                "Toolchain {} is unavailable ({}); install it with `rustup toolchain install {}` or select another with `--toolchain`",
+                toolchain_name,
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim(),
+                toolchain.unwrap_or("nightly"),
+            ),
+        ));
+    }
+
+    let version = String::from_utf8_lossy(&output.stdout);
+    let version = version.trim();
+    let is_nightly = parse_version(version)
+        .map(|parsed| is_nightly(&parsed))
+        .ok_or_else(|| {
+            crate::Error::new(
+                crate::ErrorKind::Toolchain,
+                format!(
+                    "Failed to parse the version of {}: `{}`",
+                    toolchain_name, version
+                ),
+            )
+        })?;
+    if !is_nightly {
+        return Err(crate::Error::new(
+            crate::ErrorKind::Toolchain,
+            format!(
+                "{} does not support `--output-format=json` as it is not a nightly toolchain; install one with `rustup toolchain install nightly` and select it with `--toolchain nightly`",
+                version
+            ),
+        ));
+    }
+
+    Ok(version.to_owned())
+}
+
+/// Parse the version out of `rustdoc --version`, like `rustdoc 1.60.0-nightly (9ad5d82f8 2022-01-18)`
+fn parse_version(version: &str) -> Option<semver::Version> {
+    let mut words = version.split_whitespace();
+    if words.next()? != "rustdoc" {
+        return None;
+    }
+    semver::Version::parse(words.next()?).ok()
+}
+
+/// Whether unstable features like `--output-format=json` are available
+fn is_nightly(version: &semver::Version) -> bool {
+    let channel = version.pre.as_str();
+    channel == "nightly" || channel == "dev"
 }
 
 impl Default for RustDocBuilder {
@@ -167,10 +443,18 @@ pub fn parse_raw(raw: &str, manifest_path: &std::path::Path) -> Result<crate::Ap
     RustDocParser::new().parse(raw, manifest_path)
 }
 
+/// Parse rustdoc's JSON output, if it is in one of the `SUPPORTED_FORMAT_VERSIONS`
+pub fn parse_raw_crate(
+    raw: &str,
+    manifest_path: &std::path::Path,
+) -> Result<rustdoc_json_types_fork::Crate, crate::Error> {
+    format::parse_crate(raw, manifest_path)
+}
+
 #[derive(Default)]
 struct RustDocParser {
     unprocessed: VecDeque<(Option<crate::PathId>, rustdoc_json_types_fork::Id)>,
-    deferred_imports: Vec<(crate::PathId, String, rustdoc_json_types_fork::Id)>,
+    deferred_imports: Vec<(crate::PathId, String, bool, rustdoc_json_types_fork::Id)>,
 
     api: crate::Api,
     crate_ids: HashMap<u32, Option<crate::CrateId>>,
@@ -188,23 +472,17 @@ impl RustDocParser {
         raw: &str,
         manifest_path: &std::path::Path,
     ) -> Result<crate::Api, crate::Error> {
-        let raw: rustdoc_json_types_fork::Crate = serde_json::from_str(raw).map_err(|e| {
-            crate::Error::new(
-                crate::ErrorKind::ApiParse,
-                format!(
-                    "Failed when parsing json for {}: {}",
-                    manifest_path.display(),
-                    e
-                ),
-            )
-        })?;
+        let raw = format::parse_crate(raw, manifest_path)?;
 
         self.unprocessed.push_back((None, raw.root.clone()));
         while let Some((parent_path_id, raw_item_id)) = self.unprocessed.pop_front() {
-            let raw_item = raw
-                .index
-                .get(&raw_item_id)
-                .expect("all item ids are in `index`");
+            let raw_item = match raw.index.get(&raw_item_id) {
+                Some(raw_item) => raw_item,
+                None => {
+                    self._parse_external_path(&raw, parent_path_id, &raw_item_id);
+                    continue;
+                }
+            };
 
             let crate_id = self._parse_crate(&raw, raw_item.crate_id);
 
@@ -215,7 +493,7 @@ impl RustDocParser {
             self._parse_item(&raw, &raw_item_id, path_id, crate_id);
         }
 
-        for (parent_path_id, name, raw_target_id) in self.deferred_imports {
+        for (parent_path_id, name, glob, raw_target_id) in self.deferred_imports {
             let target_path_id = self.path_ids.get(&raw_target_id).unwrap().unwrap();
             let target_path = self
                 .api
@@ -229,12 +507,23 @@ impl RustDocParser {
                 .paths
                 .get(parent_path_id)
                 .expect("all ids are valid");
+            let (name, target) = if glob {
+                ("*".to_owned(), format!("{}::*", target_path.path))
+            } else {
+                (name, target_path.path.clone())
+            };
+            let signature = if glob || target_path.path.rsplit("::").next() == Some(name.as_str()) {
+                format!("pub use {};", target)
+            } else {
+                format!("pub use {} as {};", target, name)
+            };
             let name = format!("{}::{}", parent_path.path, name);
 
             let kind = crate::PathKind::Import;
 
             let mut path = crate::Path::new(kind, name);
             path.crate_id = parent_path.crate_id;
+            path.signature = Some(signature);
             path.item_id = target_path.item_id;
             path.children = target_path.children.clone();
             let path_id = self.api.paths.push(path);
@@ -264,13 +553,70 @@ impl RustDocParser {
                 .external_crates
                 .get(&raw_crate_id)
                 .expect("all crate ids are in `external_crates`");
-            let crate_ = crate::Crate::new(&raw_crate.name);
+            let mut crate_ = crate::Crate::new(&raw_crate.name);
+            crate_.html_root_url = raw_crate.html_root_url.clone();
             self.api.crates.push(crate_)
         });
         self.crate_ids.insert(raw_crate_id, crate_id);
         crate_id
     }
 
+    fn _parse_exposed_crates(
+        &mut self,
+        raw: &rustdoc_json_types_fork::Crate,
+        raw_item: &rustdoc_json_types_fork::Item,
+        path_id: crate::PathId,
+        crate_id: Option<crate::CrateId>,
+    ) {
+        let mut raw_crate_ids = RawCrateIds::new();
+        _visit_item(raw, raw_item, &mut raw_crate_ids);
+
+        let mut crate_ids = Vec::new();
+        for (raw_crate_id, extern_names) in raw_crate_ids {
+            let is_sysroot = raw
+                .external_crates
+                .get(&raw_crate_id)
+                .map(|raw_crate| SYSROOT_CRATES.contains(&raw_crate.name.as_str()))
+                .unwrap_or(true);
+            if is_sysroot {
+                continue;
+            }
+            if let Some(crate_id) = self._parse_crate(raw, raw_crate_id) {
+                self._parse_extern_names(crate_id, extern_names);
+                crate_ids.push(crate_id);
+            }
+        }
+        crate_ids.extend(crate_id);
+
+        for crate_id in crate_ids {
+            let crate_ = self
+                .api
+                .crates
+                .get_mut(crate_id)
+                .expect("crate_id to always be valid");
+            if !crate_.exposed_by.contains(&path_id) {
+                crate_.exposed_by.push(path_id);
+            }
+        }
+    }
+
+    fn _parse_extern_names(
+        &mut self,
+        crate_id: crate::CrateId,
+        extern_names: impl IntoIterator<Item = String>,
+    ) {
+        let crate_ = self
+            .api
+            .crates
+            .get_mut(crate_id)
+            .expect("crate_id to always be valid");
+        for extern_name in extern_names {
+            if !crate_.extern_names.contains(&extern_name) {
+                crate_.extern_names.push(extern_name);
+            }
+        }
+    }
+
     fn _parse_path(
         &mut self,
         raw: &rustdoc_json_types_fork::Crate,
@@ -283,20 +629,21 @@ impl RustDocParser {
         }
 
         let path_id = raw.paths.get(raw_item_id).map(|raw_path| {
-            let raw_item = raw
-                .index
-                .get(raw_item_id)
-                .expect("all item ids are in `index`");
-
             let kind = _convert_path_kind(raw_path.kind.clone());
 
             let mut path = crate::Path::new(kind, raw_path.path.join("::"));
             path.crate_id = crate_id;
-            path.span = raw_item.span.clone().map(|raw_span| crate::Span {
-                filename: raw_span.filename,
-                begin: raw_span.begin,
-                end: raw_span.end,
-            });
+            if let Some(raw_item) = raw.index.get(raw_item_id) {
+                path.span = raw_item.span.clone().map(|raw_span| crate::Span {
+                    filename: raw_span.filename,
+                    begin: raw_span.begin,
+                    end: raw_span.end,
+                });
+                // Variants only make sense within their enum's signature
+                if kind != crate::PathKind::Variant {
+                    path.signature = signature::render_item(raw, raw_item);
+                }
+            }
             let path_id = self.api.paths.push(path);
 
             if let Some(parent_path_id) = parent_path_id {
@@ -314,6 +661,42 @@ impl RustDocParser {
         path_id
     }
 
+    /// Parse a re-exported item from a dependency, which only newer formats leave out of `index`
+    fn _parse_external_path(
+        &mut self,
+        raw: &rustdoc_json_types_fork::Crate,
+        parent_path_id: Option<crate::PathId>,
+        raw_item_id: &rustdoc_json_types_fork::Id,
+    ) {
+        let raw_crate_id = match raw.paths.get(raw_item_id) {
+            Some(raw_path) => raw_path.crate_id,
+            None => {
+                self.path_ids.insert(raw_item_id.clone(), None);
+                return;
+            }
+        };
+        let crate_id = self._parse_crate(raw, raw_crate_id);
+        let path_id = match self._parse_path(raw, parent_path_id, raw_item_id, crate_id) {
+            Some(path_id) => path_id,
+            None => return,
+        };
+        let is_sysroot = raw
+            .external_crates
+            .get(&raw_crate_id)
+            .map(|raw_crate| SYSROOT_CRATES.contains(&raw_crate.name.as_str()))
+            .unwrap_or(true);
+        if let Some(crate_id) = crate_id.filter(|_| !is_sysroot) {
+            let crate_ = self
+                .api
+                .crates
+                .get_mut(crate_id)
+                .expect("crate_id to always be valid");
+            if !crate_.exposed_by.contains(&path_id) {
+                crate_.exposed_by.push(path_id);
+            }
+        }
+    }
+
     fn _parse_item(
         &mut self,
         raw: &rustdoc_json_types_fork::Crate,
@@ -338,10 +721,16 @@ impl RustDocParser {
             }
             rustdoc_json_types_fork::ItemEnum::Import(import) => {
                 let raw_target_id = import.id.as_ref().unwrap();
+                if let Some(target_crate_id) = _raw_crate_id(raw, raw_target_id)
+                    .and_then(|raw_crate_id| self._parse_crate(raw, raw_crate_id))
+                {
+                    self._parse_extern_names(target_crate_id, _extern_name(&import.source));
+                }
                 self.unprocessed.push_back((path_id, raw_target_id.clone()));
                 self.deferred_imports.push((
                     path_id.unwrap(),
                     import.name.clone(),
+                    import.glob,
                     raw_target_id.clone(),
                 ));
                 None
@@ -383,11 +772,342 @@ impl RustDocParser {
                 Some(item_id)
             }
         };
+        if let Some(path_id) = path_id {
+            self._parse_exposed_crates(raw, raw_item, path_id, crate_id);
+        }
         self.item_ids.insert(raw_item_id.clone(), item_id);
         item_id
     }
 }
 
+/// Crates that ship with the toolchain and aren't considered public dependencies
+const SYSROOT_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// The crates referenced by a signature and the names they were referred to by in the source
+type RawCrateIds = BTreeMap<u32, BTreeSet<String>>;
+
+fn _raw_crate_id(
+    raw: &rustdoc_json_types_fork::Crate,
+    id: &rustdoc_json_types_fork::Id,
+) -> Option<u32> {
+    raw.paths
+        .get(id)
+        .map(|raw_path| raw_path.crate_id)
+        .or_else(|| raw.index.get(id).map(|raw_item| raw_item.crate_id))
+}
+
+/// The name a path was qualified with, like `dep` in `dep::Item`
+///
+/// This is how the dependency is named in `Cargo.toml`, which tells apart multiple versions
+/// of the same crate.
+fn _extern_name(path: &str) -> Option<String> {
+    let (first, _) = path.trim_start_matches("::").split_once("::")?;
+    (!matches!(first, "crate" | "self" | "super" | "$crate")).then(|| first.to_owned())
+}
+
+/// Collect the crates referenced by the signature of `raw_item`
+fn _visit_item(
+    raw: &rustdoc_json_types_fork::Crate,
+    raw_item: &rustdoc_json_types_fork::Item,
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    match &raw_item.inner {
+        rustdoc_json_types_fork::ItemEnum::Union(union_) => {
+            _visit_generics(raw, &union_.generics, raw_crate_ids);
+            _visit_fields(raw, &union_.fields, raw_crate_ids);
+            _visit_impls(raw, &union_.impls, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::Struct(struct_) => {
+            _visit_generics(raw, &struct_.generics, raw_crate_ids);
+            _visit_fields(raw, &struct_.fields, raw_crate_ids);
+            _visit_impls(raw, &struct_.impls, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::StructField(type_) => {
+            _visit_type(raw, type_, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::Enum(enum_) => {
+            _visit_generics(raw, &enum_.generics, raw_crate_ids);
+            _visit_impls(raw, &enum_.impls, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::Variant(variant) => match variant {
+            rustdoc_json_types_fork::Variant::Plain => {}
+            rustdoc_json_types_fork::Variant::Tuple(types) => {
+                for type_ in types {
+                    _visit_type(raw, type_, raw_crate_ids);
+                }
+            }
+            rustdoc_json_types_fork::Variant::Struct(fields) => {
+                _visit_fields(raw, fields, raw_crate_ids);
+            }
+        },
+        rustdoc_json_types_fork::ItemEnum::Function(function) => {
+            _visit_generics(raw, &function.generics, raw_crate_ids);
+            _visit_decl(raw, &function.decl, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::Method(method) => {
+            _visit_generics(raw, &method.generics, raw_crate_ids);
+            _visit_decl(raw, &method.decl, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::Trait(trait_) => {
+            _visit_generics(raw, &trait_.generics, raw_crate_ids);
+            _visit_bounds(raw, &trait_.bounds, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::TraitAlias(alias) => {
+            _visit_generics(raw, &alias.generics, raw_crate_ids);
+            _visit_bounds(raw, &alias.params, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::Typedef(typedef) => {
+            _visit_generics(raw, &typedef.generics, raw_crate_ids);
+            _visit_type(raw, &typedef.type_, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::OpaqueTy(opaque) => {
+            _visit_generics(raw, &opaque.generics, raw_crate_ids);
+            _visit_bounds(raw, &opaque.bounds, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::Constant(constant) => {
+            _visit_type(raw, &constant.type_, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::Static(static_) => {
+            _visit_type(raw, &static_.type_, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::AssocConst { type_, .. } => {
+            _visit_type(raw, type_, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::ItemEnum::AssocType { bounds, default } => {
+            _visit_bounds(raw, bounds, raw_crate_ids);
+            if let Some(default) = default {
+                _visit_type(raw, default, raw_crate_ids);
+            }
+        }
+        rustdoc_json_types_fork::ItemEnum::Module(_)
+        | rustdoc_json_types_fork::ItemEnum::ExternCrate { .. }
+        | rustdoc_json_types_fork::ItemEnum::Import(_)
+        | rustdoc_json_types_fork::ItemEnum::Impl(_)
+        | rustdoc_json_types_fork::ItemEnum::ForeignType
+        | rustdoc_json_types_fork::ItemEnum::Macro(_)
+        | rustdoc_json_types_fork::ItemEnum::ProcMacro(_)
+        | rustdoc_json_types_fork::ItemEnum::PrimitiveType(_) => {}
+    }
+}
+
+fn _visit_fields(
+    raw: &rustdoc_json_types_fork::Crate,
+    raw_field_ids: &[rustdoc_json_types_fork::Id],
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    for raw_field_id in raw_field_ids {
+        if let Some(raw_field) = raw.index.get(raw_field_id) {
+            if raw_field.visibility == rustdoc_json_types_fork::Visibility::Public {
+                _visit_item(raw, raw_field, raw_crate_ids);
+            }
+        }
+    }
+}
+
+fn _visit_impls(
+    raw: &rustdoc_json_types_fork::Crate,
+    raw_impl_ids: &[rustdoc_json_types_fork::Id],
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    for raw_impl_id in raw_impl_ids {
+        let impl_ = match raw.index.get(raw_impl_id).map(|raw_impl| &raw_impl.inner) {
+            Some(rustdoc_json_types_fork::ItemEnum::Impl(impl_)) => impl_,
+            _ => continue,
+        };
+        // Auto-traits and blanket impls are implied by the type, rather than exposing anything
+        if impl_.synthetic || impl_.blanket_impl.is_some() {
+            continue;
+        }
+
+        _visit_generics(raw, &impl_.generics, raw_crate_ids);
+        if let Some(trait_) = impl_.trait_.as_ref() {
+            _visit_type(raw, trait_, raw_crate_ids);
+        }
+        for raw_item_id in &impl_.items {
+            if let Some(raw_item) = raw.index.get(raw_item_id) {
+                if matches!(
+                    raw_item.visibility,
+                    rustdoc_json_types_fork::Visibility::Public
+                        | rustdoc_json_types_fork::Visibility::Default
+                ) {
+                    _visit_item(raw, raw_item, raw_crate_ids);
+                }
+            }
+        }
+    }
+}
+
+fn _visit_generics(
+    raw: &rustdoc_json_types_fork::Crate,
+    generics: &rustdoc_json_types_fork::Generics,
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    _visit_generic_params(raw, &generics.params, raw_crate_ids);
+    for predicate in &generics.where_predicates {
+        match predicate {
+            rustdoc_json_types_fork::WherePredicate::BoundPredicate { ty, bounds } => {
+                _visit_type(raw, ty, raw_crate_ids);
+                _visit_bounds(raw, bounds, raw_crate_ids);
+            }
+            rustdoc_json_types_fork::WherePredicate::RegionPredicate { bounds, .. } => {
+                _visit_bounds(raw, bounds, raw_crate_ids);
+            }
+            rustdoc_json_types_fork::WherePredicate::EqPredicate { lhs, rhs } => {
+                _visit_type(raw, lhs, raw_crate_ids);
+                _visit_type(raw, rhs, raw_crate_ids);
+            }
+        }
+    }
+}
+
+fn _visit_generic_params(
+    raw: &rustdoc_json_types_fork::Crate,
+    params: &[rustdoc_json_types_fork::GenericParamDef],
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    for param in params {
+        match &param.kind {
+            rustdoc_json_types_fork::GenericParamDefKind::Lifetime { .. } => {}
+            rustdoc_json_types_fork::GenericParamDefKind::Type { bounds, default } => {
+                _visit_bounds(raw, bounds, raw_crate_ids);
+                if let Some(default) = default {
+                    _visit_type(raw, default, raw_crate_ids);
+                }
+            }
+            rustdoc_json_types_fork::GenericParamDefKind::Const { ty, .. } => {
+                _visit_type(raw, ty, raw_crate_ids);
+            }
+        }
+    }
+}
+
+fn _visit_bounds(
+    raw: &rustdoc_json_types_fork::Crate,
+    bounds: &[rustdoc_json_types_fork::GenericBound],
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    for bound in bounds {
+        match bound {
+            rustdoc_json_types_fork::GenericBound::TraitBound {
+                trait_,
+                generic_params,
+                ..
+            } => {
+                _visit_type(raw, trait_, raw_crate_ids);
+                _visit_generic_params(raw, generic_params, raw_crate_ids);
+            }
+            rustdoc_json_types_fork::GenericBound::Outlives(_) => {}
+        }
+    }
+}
+
+fn _visit_decl(
+    raw: &rustdoc_json_types_fork::Crate,
+    decl: &rustdoc_json_types_fork::FnDecl,
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    for (_, input) in &decl.inputs {
+        _visit_type(raw, input, raw_crate_ids);
+    }
+    if let Some(output) = decl.output.as_ref() {
+        _visit_type(raw, output, raw_crate_ids);
+    }
+}
+
+fn _visit_type(
+    raw: &rustdoc_json_types_fork::Crate,
+    type_: &rustdoc_json_types_fork::Type,
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    match type_ {
+        rustdoc_json_types_fork::Type::ResolvedPath {
+            name,
+            id,
+            args,
+            param_names,
+        } => {
+            if let Some(raw_crate_id) = _raw_crate_id(raw, id) {
+                raw_crate_ids
+                    .entry(raw_crate_id)
+                    .or_default()
+                    .extend(_extern_name(name));
+            }
+            if let Some(args) = args.as_deref() {
+                _visit_generic_args(raw, args, raw_crate_ids);
+            }
+            _visit_bounds(raw, param_names, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::Type::Generic(_)
+        | rustdoc_json_types_fork::Type::Primitive(_)
+        | rustdoc_json_types_fork::Type::Infer => {}
+        rustdoc_json_types_fork::Type::FunctionPointer(pointer) => {
+            _visit_generic_params(raw, &pointer.generic_params, raw_crate_ids);
+            _visit_decl(raw, &pointer.decl, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::Type::Tuple(types) => {
+            for type_ in types {
+                _visit_type(raw, type_, raw_crate_ids);
+            }
+        }
+        rustdoc_json_types_fork::Type::Slice(type_)
+        | rustdoc_json_types_fork::Type::Array { type_, .. }
+        | rustdoc_json_types_fork::Type::RawPointer { type_, .. }
+        | rustdoc_json_types_fork::Type::BorrowedRef { type_, .. } => {
+            _visit_type(raw, type_, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::Type::ImplTrait(bounds) => {
+            _visit_bounds(raw, bounds, raw_crate_ids);
+        }
+        rustdoc_json_types_fork::Type::QualifiedPath {
+            self_type, trait_, ..
+        } => {
+            _visit_type(raw, self_type, raw_crate_ids);
+            _visit_type(raw, trait_, raw_crate_ids);
+        }
+    }
+}
+
+fn _visit_generic_args(
+    raw: &rustdoc_json_types_fork::Crate,
+    args: &rustdoc_json_types_fork::GenericArgs,
+    raw_crate_ids: &mut RawCrateIds,
+) {
+    match args {
+        rustdoc_json_types_fork::GenericArgs::AngleBracketed { args, bindings } => {
+            for arg in args {
+                match arg {
+                    rustdoc_json_types_fork::GenericArg::Lifetime(_)
+                    | rustdoc_json_types_fork::GenericArg::Infer => {}
+                    rustdoc_json_types_fork::GenericArg::Type(type_) => {
+                        _visit_type(raw, type_, raw_crate_ids);
+                    }
+                    rustdoc_json_types_fork::GenericArg::Const(constant) => {
+                        _visit_type(raw, &constant.type_, raw_crate_ids);
+                    }
+                }
+            }
+            for binding in bindings {
+                match &binding.binding {
+                    rustdoc_json_types_fork::TypeBindingKind::Equality(type_) => {
+                        _visit_type(raw, type_, raw_crate_ids);
+                    }
+                    rustdoc_json_types_fork::TypeBindingKind::Constraint(bounds) => {
+                        _visit_bounds(raw, bounds, raw_crate_ids);
+                    }
+                }
+            }
+        }
+        rustdoc_json_types_fork::GenericArgs::Parenthesized { inputs, output } => {
+            for input in inputs {
+                _visit_type(raw, input, raw_crate_ids);
+            }
+            if let Some(output) = output {
+                _visit_type(raw, output, raw_crate_ids);
+            }
+        }
+    }
+}
+
 fn _convert_path_kind(kind: rustdoc_json_types_fork::ItemKind) -> crate::PathKind {
     match kind {
         rustdoc_json_types_fork::ItemKind::Module => crate::PathKind::Module,
@@ -419,3 +1139,63 @@ fn _convert_path_kind(kind: rustdoc_json_types_fork::ItemKind) -> crate::PathKin
         }
     }
 }
+
+#[test]
+fn nightly_versions() {
+    let cases = [
+        ("rustdoc 1.60.0-nightly (9ad5d82f8 2022-01-18)", Some(true)),
+        ("rustdoc 1.62.0-dev", Some(true)),
+        ("rustdoc 1.59.0 (9d1b2106e 2022-02-23)", Some(false)),
+        ("rustdoc 1.60.0-beta.1 (0a4b1b3e6 2022-02-24)", Some(false)),
+        // Not fooled by the commit info
+        ("rustdoc 1.59.0 (9d1b2106e-nightly 2022-02-23)", Some(false)),
+        ("rustdoc", None),
+        ("error: toolchain 'nightly' is not installed", None),
+    ];
+    for (version, expected) in cases {
+        let actual = parse_version(version).map(|parsed| is_nightly(&parsed));
+        assert_eq!(actual, expected, "{}", version);
+    }
+}
+
+#[test]
+fn classify_cargo_errors() {
+    let cases = [
+        (
+            "error: failed to parse manifest at `/tmp/t1/Cargo.toml`
+
+Caused by:
+  failed to parse the version requirement `not a version` for dependency `foo`",
+            crate::ErrorKind::InvalidManifest,
+        ),
+        (
+            "error: failed to get `foo` as a dependency of package `t1 v0.1.0 (/tmp/t1)`
+
+Caused by:
+  failed to load source for dependency `foo`",
+            crate::ErrorKind::InvalidManifest,
+        ),
+        (
+            "error: this file contains an unclosed delimiter
+ --> src/lib.rs:1:10
+
+error: could not document `t2`",
+            crate::ErrorKind::CompileError,
+        ),
+        (
+            "error: this file contains an unclosed delimiter
+ --> /tmp/t2/src/lib.rs:1:10
+
+error: could not compile `t2` (lib) due to 1 previous error",
+            crate::ErrorKind::CompileError,
+        ),
+        ("", crate::ErrorKind::Unknown),
+        (
+            "error: failed to write `/tmp/t3/target/doc`: No space left on device",
+            crate::ErrorKind::Unknown,
+        ),
+    ];
+    for (stderr, expected) in cases {
+        assert_eq!(classify_cargo_error(stderr), expected, "{}", stderr);
+    }
+}
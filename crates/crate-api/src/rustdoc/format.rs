@@ -0,0 +1,69 @@
+//! Parse rustdoc's JSON from each format we support into `rustdoc_json_types_fork`'s format
+//!
+//! A newer format is supported by rewriting it into `rustdoc_json_types_fork::FORMAT_VERSION`,
+//! checked against fixtures generated by a toolchain emitting that format.
+
+use serde_json::Value;
+
+mod v57;
+
+/// The rustdoc JSON `format_version`s that can be parsed
+pub const SUPPORTED_FORMAT_VERSIONS: &[u32] =
+    &[rustdoc_json_types_fork::FORMAT_VERSION, v57::FORMAT_VERSION];
+
+pub(crate) fn parse_crate(
+    raw: &str,
+    manifest_path: &std::path::Path,
+) -> Result<rustdoc_json_types_fork::Crate, crate::Error> {
+    let parse_err = |e: serde_json::Error| {
+        crate::Error::new(
+            crate::ErrorKind::ApiParse,
+            format!(
+                "Failed when parsing json for {}: {}",
+                manifest_path.display(),
+                e
+            ),
+        )
+        .set_source(e)
+    };
+
+    let raw: Value = serde_json::from_str(raw).map_err(parse_err)?;
+    let format_version = raw
+        .get("format_version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| {
+            crate::Error::new(
+                crate::ErrorKind::ApiParse,
+                format!(
+                    "Failed when parsing json for {}: missing `format_version`",
+                    manifest_path.display()
+                ),
+            )
+        })?;
+    let format_version = u32::try_from(format_version)
+        .ok()
+        .filter(|v| SUPPORTED_FORMAT_VERSIONS.contains(v))
+        .ok_or_else(|| {
+            let expected = SUPPORTED_FORMAT_VERSIONS
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" or ");
+            crate::Error::new(
+                crate::ErrorKind::UnsupportedFormatVersion,
+                format!(
+                    "Unsupported rustdoc JSON format version {} for {}, expected {}",
+                    format_version,
+                    manifest_path.display(),
+                    expected
+                ),
+            )
+        })?;
+
+    if format_version == v57::FORMAT_VERSION {
+        let raw: v57::Crate = serde_json::from_value(raw).map_err(parse_err)?;
+        Ok(v57::into_v9(&raw))
+    } else {
+        serde_json::from_value(raw).map_err(parse_err)
+    }
+}
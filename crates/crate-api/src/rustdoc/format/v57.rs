@@ -0,0 +1,1100 @@
+//! rustdoc JSON `format_version` 57, rewritten into `rustdoc_json_types_fork`'s format
+//!
+//! See https://docs.rs/rustdoc-types/0.57.0/rustdoc_types/
+//!
+//! Only the fields we read are declared.  Where format 9 has no equivalent, like precise
+//! capturing or return type notation, the closest rendering is kept instead.
+
+use std::collections::{HashMap, HashSet};
+
+use rustdoc_json_types_fork as v9;
+use serde::Deserialize;
+
+pub(crate) const FORMAT_VERSION: u32 = 57;
+
+#[derive(Deserialize)]
+pub(crate) struct Crate {
+    root: Id,
+    crate_version: Option<String>,
+    includes_private: bool,
+    index: HashMap<Id, Item>,
+    paths: HashMap<Id, ItemSummary>,
+    external_crates: HashMap<u32, ExternalCrate>,
+}
+
+#[derive(Deserialize)]
+struct ExternalCrate {
+    name: String,
+    html_root_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ItemSummary {
+    crate_id: u32,
+    path: Vec<String>,
+    kind: ItemKind,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    id: Id,
+    crate_id: u32,
+    name: Option<String>,
+    span: Option<Span>,
+    visibility: Visibility,
+    docs: Option<String>,
+    links: HashMap<String, Id>,
+    attrs: Vec<Attribute>,
+    deprecation: Option<v9::Deprecation>,
+    inner: ItemEnum,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Attribute {
+    NonExhaustive,
+    MustUse { reason: Option<String> },
+    MacroExport,
+    ExportName(String),
+    LinkSection(String),
+    AutomaticallyDerived,
+    Repr(AttributeRepr),
+    NoMangle,
+    TargetFeature { enable: Vec<String> },
+    Other(String),
+}
+
+/// Unlike format 9, columns are 1-indexed
+#[derive(Deserialize)]
+struct Span {
+    filename: std::path::PathBuf,
+    begin: (usize, usize),
+    end: (usize, usize),
+}
+
+#[derive(Deserialize)]
+struct AttributeRepr {
+    kind: ReprKind,
+    align: Option<u64>,
+    packed: Option<u64>,
+    int: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReprKind {
+    Rust,
+    C,
+    Transparent,
+    Simd,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Visibility {
+    Public,
+    Default,
+    Crate,
+    Restricted { parent: Id, path: String },
+}
+
+#[derive(Deserialize)]
+struct DynTrait {
+    traits: Vec<PolyTrait>,
+    lifetime: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PolyTrait {
+    #[serde(rename = "trait")]
+    trait_: Path,
+    generic_params: Vec<GenericParamDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum GenericArgs {
+    AngleBracketed {
+        args: Vec<GenericArg>,
+        constraints: Vec<AssocItemConstraint>,
+    },
+    Parenthesized {
+        inputs: Vec<Type>,
+        output: Option<Type>,
+    },
+    ReturnTypeNotation,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum GenericArg {
+    Lifetime(String),
+    Type(Type),
+    Const(Constant),
+    Infer,
+}
+
+#[derive(Deserialize)]
+struct Constant {
+    expr: String,
+    value: Option<String>,
+    is_literal: bool,
+}
+
+#[derive(Deserialize)]
+struct AssocItemConstraint {
+    name: String,
+    binding: AssocItemConstraintKind,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AssocItemConstraintKind {
+    Equality(Term),
+    Constraint(Vec<GenericBound>),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+struct Id(u32);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ItemKind {
+    Module,
+    ExternCrate,
+    Use,
+    Struct,
+    StructField,
+    Union,
+    Enum,
+    Variant,
+    Function,
+    TypeAlias,
+    Constant,
+    Trait,
+    TraitAlias,
+    Impl,
+    Static,
+    ExternType,
+    Macro,
+    ProcAttribute,
+    ProcDerive,
+    AssocConst,
+    AssocType,
+    Primitive,
+    Keyword,
+    Attribute,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ItemEnum {
+    Module(Module),
+    ExternCrate {
+        name: String,
+        rename: Option<String>,
+    },
+    Use(Use),
+    Union(Union),
+    Struct(Struct),
+    StructField(Type),
+    Enum(Enum),
+    Variant(Variant),
+    Function(Function),
+    Trait(Trait),
+    TraitAlias(TraitAlias),
+    Impl(Impl),
+    TypeAlias(TypeAlias),
+    Constant {
+        #[serde(rename = "type")]
+        type_: Type,
+        #[serde(rename = "const")]
+        const_: Constant,
+    },
+    Static(Static),
+    ExternType,
+    Macro(String),
+    ProcMacro(v9::ProcMacro),
+    Primitive(Primitive),
+    AssocConst {
+        #[serde(rename = "type")]
+        type_: Type,
+        value: Option<String>,
+    },
+    AssocType {
+        bounds: Vec<GenericBound>,
+        #[serde(rename = "type")]
+        type_: Option<Type>,
+    },
+}
+
+#[derive(Deserialize)]
+struct Module {
+    is_crate: bool,
+    items: Vec<Id>,
+}
+
+#[derive(Deserialize)]
+struct Union {
+    generics: Generics,
+    has_stripped_fields: bool,
+    fields: Vec<Id>,
+    impls: Vec<Id>,
+}
+
+#[derive(Deserialize)]
+struct Struct {
+    kind: StructKind,
+    generics: Generics,
+    impls: Vec<Id>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StructKind {
+    Unit,
+    Tuple(Vec<Option<Id>>),
+    Plain {
+        fields: Vec<Id>,
+        has_stripped_fields: bool,
+    },
+}
+
+#[derive(Deserialize)]
+struct Enum {
+    generics: Generics,
+    has_stripped_variants: bool,
+    variants: Vec<Id>,
+    impls: Vec<Id>,
+}
+
+#[derive(Deserialize)]
+struct Variant {
+    kind: VariantKind,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum VariantKind {
+    Plain,
+    Tuple(Vec<Option<Id>>),
+    Struct { fields: Vec<Id> },
+}
+
+#[derive(Deserialize)]
+struct FunctionHeader {
+    is_const: bool,
+    is_unsafe: bool,
+    is_async: bool,
+    abi: Abi,
+}
+
+#[derive(Deserialize)]
+enum Abi {
+    Rust,
+    C { unwind: bool },
+    Cdecl { unwind: bool },
+    Stdcall { unwind: bool },
+    Fastcall { unwind: bool },
+    Aapcs { unwind: bool },
+    Win64 { unwind: bool },
+    SysV64 { unwind: bool },
+    System { unwind: bool },
+    Other(String),
+}
+
+#[derive(Deserialize)]
+struct Function {
+    sig: FunctionSignature,
+    generics: Generics,
+    header: FunctionHeader,
+    has_body: bool,
+}
+
+#[derive(Deserialize)]
+struct Generics {
+    params: Vec<GenericParamDef>,
+    where_predicates: Vec<WherePredicate>,
+}
+
+#[derive(Deserialize)]
+struct GenericParamDef {
+    name: String,
+    kind: GenericParamDefKind,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum GenericParamDefKind {
+    Lifetime {
+        outlives: Vec<String>,
+    },
+    Type {
+        bounds: Vec<GenericBound>,
+        default: Option<Type>,
+    },
+    Const {
+        #[serde(rename = "type")]
+        type_: Type,
+        default: Option<String>,
+    },
+}
+
+// Named after rustdoc's variants
+#[allow(clippy::enum_variant_names)]
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum WherePredicate {
+    BoundPredicate {
+        #[serde(rename = "type")]
+        type_: Type,
+        bounds: Vec<GenericBound>,
+    },
+    LifetimePredicate {
+        lifetime: String,
+        outlives: Vec<String>,
+    },
+    EqPredicate {
+        lhs: Type,
+        rhs: Term,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum GenericBound {
+    TraitBound {
+        #[serde(rename = "trait")]
+        trait_: Path,
+        generic_params: Vec<GenericParamDef>,
+        modifier: v9::TraitBoundModifier,
+    },
+    Outlives(String),
+    Use(Vec<PreciseCapturingArg>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PreciseCapturingArg {
+    Lifetime(String),
+    Param(String),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Term {
+    Type(Type),
+    Constant(Constant),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Type {
+    ResolvedPath(Path),
+    DynTrait(DynTrait),
+    Generic(String),
+    Primitive(String),
+    FunctionPointer(Box<FunctionPointer>),
+    Tuple(Vec<Type>),
+    Slice(Box<Type>),
+    Array {
+        #[serde(rename = "type")]
+        type_: Box<Type>,
+        len: String,
+    },
+    Pat {
+        #[serde(rename = "type")]
+        type_: Box<Type>,
+    },
+    ImplTrait(Vec<GenericBound>),
+    Infer,
+    RawPointer {
+        is_mutable: bool,
+        #[serde(rename = "type")]
+        type_: Box<Type>,
+    },
+    BorrowedRef {
+        lifetime: Option<String>,
+        is_mutable: bool,
+        #[serde(rename = "type")]
+        type_: Box<Type>,
+    },
+    QualifiedPath {
+        name: String,
+        self_type: Box<Type>,
+        #[serde(rename = "trait")]
+        trait_: Option<Path>,
+    },
+}
+
+#[derive(Deserialize)]
+struct Path {
+    path: String,
+    id: Id,
+    args: Option<Box<GenericArgs>>,
+}
+
+#[derive(Deserialize)]
+struct FunctionPointer {
+    sig: FunctionSignature,
+    generic_params: Vec<GenericParamDef>,
+    header: FunctionHeader,
+}
+
+#[derive(Deserialize)]
+struct FunctionSignature {
+    inputs: Vec<(String, Type)>,
+    output: Option<Type>,
+    is_c_variadic: bool,
+}
+
+#[derive(Deserialize)]
+struct Trait {
+    is_auto: bool,
+    is_unsafe: bool,
+    items: Vec<Id>,
+    generics: Generics,
+    bounds: Vec<GenericBound>,
+    implementations: Vec<Id>,
+}
+
+#[derive(Deserialize)]
+struct TraitAlias {
+    generics: Generics,
+    params: Vec<GenericBound>,
+}
+
+#[derive(Deserialize)]
+struct Impl {
+    is_unsafe: bool,
+    generics: Generics,
+    provided_trait_methods: Vec<String>,
+    #[serde(rename = "trait")]
+    trait_: Option<Path>,
+    #[serde(rename = "for")]
+    for_: Type,
+    items: Vec<Id>,
+    is_negative: bool,
+    is_synthetic: bool,
+    blanket_impl: Option<Type>,
+}
+
+#[derive(Deserialize)]
+struct Use {
+    source: String,
+    name: String,
+    id: Option<Id>,
+    is_glob: bool,
+}
+
+#[derive(Deserialize)]
+struct TypeAlias {
+    #[serde(rename = "type")]
+    type_: Type,
+    generics: Generics,
+}
+
+#[derive(Deserialize)]
+struct Static {
+    #[serde(rename = "type")]
+    type_: Type,
+    is_mutable: bool,
+    expr: String,
+}
+
+#[derive(Deserialize)]
+struct Primitive {
+    name: String,
+}
+
+/// Rewrite `raw` into format 9
+pub(crate) fn into_v9(raw: &Crate) -> v9::Crate {
+    // Format 57 merged methods into functions
+    let methods: HashSet<Id> = raw
+        .index
+        .values()
+        .flat_map(|raw_item| match &raw_item.inner {
+            ItemEnum::Trait(trait_) => trait_.items.as_slice(),
+            ItemEnum::Impl(impl_) => impl_.items.as_slice(),
+            _ => &[],
+        })
+        .copied()
+        .collect();
+
+    let index = raw
+        .index
+        .iter()
+        .map(|(id, raw_item)| {
+            let is_method = methods.contains(id);
+            (convert_id(*id), convert_item(raw, raw_item, is_method))
+        })
+        .collect();
+    let paths = raw
+        .paths
+        .iter()
+        .map(|(id, summary)| {
+            (
+                convert_id(*id),
+                v9::ItemSummary {
+                    crate_id: summary.crate_id,
+                    path: summary.path.clone(),
+                    kind: convert_kind(&summary.kind),
+                },
+            )
+        })
+        .collect();
+    let external_crates = raw
+        .external_crates
+        .iter()
+        .map(|(id, raw_crate)| {
+            (
+                *id,
+                v9::ExternalCrate {
+                    name: raw_crate.name.clone(),
+                    html_root_url: raw_crate.html_root_url.clone(),
+                },
+            )
+        })
+        .collect();
+    v9::Crate {
+        root: convert_id(raw.root),
+        crate_version: raw.crate_version.clone(),
+        includes_private: raw.includes_private,
+        index,
+        paths,
+        external_crates,
+        format_version: v9::FORMAT_VERSION,
+    }
+}
+
+fn convert_id(id: Id) -> v9::Id {
+    v9::Id(id.0.to_string())
+}
+
+fn convert_ids(ids: &[Id]) -> Vec<v9::Id> {
+    ids.iter().copied().map(convert_id).collect()
+}
+
+fn convert_kind(kind: &ItemKind) -> v9::ItemKind {
+    match kind {
+        ItemKind::Module => v9::ItemKind::Module,
+        ItemKind::ExternCrate => v9::ItemKind::ExternCrate,
+        ItemKind::Use => v9::ItemKind::Import,
+        ItemKind::Struct => v9::ItemKind::Struct,
+        ItemKind::StructField => v9::ItemKind::StructField,
+        ItemKind::Union => v9::ItemKind::Union,
+        ItemKind::Enum => v9::ItemKind::Enum,
+        ItemKind::Variant => v9::ItemKind::Variant,
+        ItemKind::Function => v9::ItemKind::Function,
+        ItemKind::TypeAlias => v9::ItemKind::Typedef,
+        ItemKind::Constant => v9::ItemKind::Constant,
+        ItemKind::Trait => v9::ItemKind::Trait,
+        ItemKind::TraitAlias => v9::ItemKind::TraitAlias,
+        ItemKind::Impl => v9::ItemKind::Impl,
+        ItemKind::Static => v9::ItemKind::Static,
+        ItemKind::ExternType => v9::ItemKind::ForeignType,
+        ItemKind::Macro => v9::ItemKind::Macro,
+        ItemKind::ProcAttribute => v9::ItemKind::ProcAttribute,
+        ItemKind::ProcDerive => v9::ItemKind::ProcDerive,
+        ItemKind::AssocConst => v9::ItemKind::AssocConst,
+        ItemKind::AssocType => v9::ItemKind::AssocType,
+        ItemKind::Primitive => v9::ItemKind::Primitive,
+        // Both only exist to document the language
+        ItemKind::Keyword | ItemKind::Attribute => v9::ItemKind::Keyword,
+    }
+}
+
+fn convert_item(raw: &Crate, raw_item: &Item, is_method: bool) -> v9::Item {
+    v9::Item {
+        id: convert_id(raw_item.id),
+        crate_id: raw_item.crate_id,
+        name: raw_item.name.clone(),
+        span: raw_item.span.as_ref().map(convert_span),
+        visibility: convert_visibility(&raw_item.visibility),
+        docs: raw_item.docs.clone(),
+        links: raw_item
+            .links
+            .iter()
+            .map(|(link, id)| (link.clone(), convert_id(*id)))
+            .collect(),
+        attrs: raw_item.attrs.iter().map(convert_attr).collect(),
+        deprecation: raw_item.deprecation.clone(),
+        inner: convert_inner(raw, &raw_item.inner, is_method),
+    }
+}
+
+/// Render the attribute as source, like format 9 did
+fn convert_span(span: &Span) -> v9::Span {
+    v9::Span {
+        filename: span.filename.clone(),
+        begin: (span.begin.0, span.begin.1.saturating_sub(1)),
+        end: (span.end.0, span.end.1.saturating_sub(1)),
+    }
+}
+
+fn convert_attr(attr: &Attribute) -> String {
+    match attr {
+        Attribute::NonExhaustive => "#[non_exhaustive]".to_owned(),
+        Attribute::MustUse { reason: None } => "#[must_use]".to_owned(),
+        Attribute::MustUse {
+            reason: Some(reason),
+        } => format!("#[must_use = {:?}]", reason),
+        Attribute::MacroExport => "#[macro_export]".to_owned(),
+        Attribute::ExportName(name) => format!("#[export_name = {:?}]", name),
+        Attribute::LinkSection(section) => format!("#[link_section = {:?}]", section),
+        Attribute::AutomaticallyDerived => "#[automatically_derived]".to_owned(),
+        Attribute::Repr(repr) => {
+            let mut args = Vec::new();
+            match repr.kind {
+                ReprKind::Rust => {}
+                ReprKind::C => args.push("C".to_owned()),
+                ReprKind::Transparent => args.push("transparent".to_owned()),
+                ReprKind::Simd => args.push("simd".to_owned()),
+            }
+            args.extend(repr.int.clone());
+            args.extend(repr.align.map(|align| format!("align({})", align)));
+            args.extend(repr.packed.map(|packed| format!("packed({})", packed)));
+            if args.is_empty() {
+                args.push("Rust".to_owned());
+            }
+            format!("#[repr({})]", args.join(", "))
+        }
+        Attribute::NoMangle => "#[no_mangle]".to_owned(),
+        Attribute::TargetFeature { enable } => {
+            format!("#[target_feature(enable = {:?})]", enable.join(","))
+        }
+        Attribute::Other(attr) => attr.clone(),
+    }
+}
+
+fn convert_visibility(visibility: &Visibility) -> v9::Visibility {
+    match visibility {
+        Visibility::Public => v9::Visibility::Public,
+        Visibility::Default => v9::Visibility::Default,
+        Visibility::Crate => v9::Visibility::Crate,
+        Visibility::Restricted { parent, path } => v9::Visibility::Restricted {
+            parent: convert_id(*parent),
+            path: path.clone(),
+        },
+    }
+}
+
+fn convert_inner(raw: &Crate, inner: &ItemEnum, is_method: bool) -> v9::ItemEnum {
+    match inner {
+        ItemEnum::Module(module) => v9::ItemEnum::Module(v9::Module {
+            is_crate: module.is_crate,
+            items: convert_ids(&module.items),
+        }),
+        ItemEnum::ExternCrate { name, rename } => v9::ItemEnum::ExternCrate {
+            name: name.clone(),
+            rename: rename.clone(),
+        },
+        ItemEnum::Use(use_) => v9::ItemEnum::Import(v9::Import {
+            source: use_.source.clone(),
+            name: use_.name.clone(),
+            id: use_.id.map(convert_id),
+            glob: use_.is_glob,
+        }),
+        ItemEnum::Union(union_) => v9::ItemEnum::Union(v9::Union {
+            generics: convert_generics(&union_.generics),
+            fields_stripped: union_.has_stripped_fields,
+            fields: convert_ids(&union_.fields),
+            impls: convert_ids(&union_.impls),
+        }),
+        ItemEnum::Struct(struct_) => {
+            let (struct_type, fields, fields_stripped) = match &struct_.kind {
+                StructKind::Unit => (v9::StructType::Unit, Vec::new(), false),
+                StructKind::Tuple(fields) => {
+                    // Private tuple fields are holes
+                    let stripped = fields.iter().any(|field| field.is_none());
+                    let fields = fields.iter().flatten().copied().map(convert_id).collect();
+                    (v9::StructType::Tuple, fields, stripped)
+                }
+                StructKind::Plain {
+                    fields,
+                    has_stripped_fields,
+                } => (
+                    v9::StructType::Plain,
+                    convert_ids(fields),
+                    *has_stripped_fields,
+                ),
+            };
+            v9::ItemEnum::Struct(v9::Struct {
+                struct_type,
+                generics: convert_generics(&struct_.generics),
+                fields_stripped,
+                fields,
+                impls: convert_ids(&struct_.impls),
+            })
+        }
+        ItemEnum::StructField(type_) => v9::ItemEnum::StructField(convert_type(type_)),
+        ItemEnum::Enum(enum_) => v9::ItemEnum::Enum(v9::Enum {
+            generics: convert_generics(&enum_.generics),
+            variants_stripped: enum_.has_stripped_variants,
+            variants: convert_ids(&enum_.variants),
+            impls: convert_ids(&enum_.impls),
+        }),
+        ItemEnum::Variant(variant) => v9::ItemEnum::Variant(match &variant.kind {
+            VariantKind::Plain => v9::Variant::Plain,
+            // Format 9 held the types of tuple fields, rather than referring to the fields
+            VariantKind::Tuple(fields) => v9::Variant::Tuple(
+                fields
+                    .iter()
+                    .flatten()
+                    .filter_map(
+                        |field| match raw.index.get(field).map(|field| &field.inner) {
+                            Some(ItemEnum::StructField(type_)) => Some(convert_type(type_)),
+                            _ => None,
+                        },
+                    )
+                    .collect(),
+            ),
+            VariantKind::Struct { fields } => v9::Variant::Struct(convert_ids(fields)),
+        }),
+        ItemEnum::Function(function) => {
+            let generics = convert_generics(&function.generics);
+            let decl = convert_sig(&function.sig);
+            let (header, abi) = convert_header(&function.header);
+            if is_method {
+                v9::ItemEnum::Method(v9::Method {
+                    decl,
+                    generics,
+                    header,
+                    abi,
+                    has_body: function.has_body,
+                })
+            } else {
+                v9::ItemEnum::Function(v9::Function {
+                    decl,
+                    generics,
+                    header,
+                    abi,
+                })
+            }
+        }
+        ItemEnum::Trait(trait_) => v9::ItemEnum::Trait(v9::Trait {
+            is_auto: trait_.is_auto,
+            is_unsafe: trait_.is_unsafe,
+            items: convert_ids(&trait_.items),
+            generics: convert_generics(&trait_.generics),
+            bounds: convert_bounds(&trait_.bounds),
+            implementors: convert_ids(&trait_.implementations),
+        }),
+        ItemEnum::TraitAlias(alias) => v9::ItemEnum::TraitAlias(v9::TraitAlias {
+            generics: convert_generics(&alias.generics),
+            params: convert_bounds(&alias.params),
+        }),
+        ItemEnum::Impl(impl_) => v9::ItemEnum::Impl(v9::Impl {
+            is_unsafe: impl_.is_unsafe,
+            generics: convert_generics(&impl_.generics),
+            provided_trait_methods: impl_.provided_trait_methods.clone(),
+            trait_: impl_.trait_.as_ref().map(convert_path),
+            for_: convert_type(&impl_.for_),
+            items: convert_ids(&impl_.items),
+            negative: impl_.is_negative,
+            synthetic: impl_.is_synthetic,
+            blanket_impl: impl_.blanket_impl.as_ref().map(convert_type),
+        }),
+        ItemEnum::TypeAlias(alias) => v9::ItemEnum::Typedef(v9::Typedef {
+            type_: convert_type(&alias.type_),
+            generics: convert_generics(&alias.generics),
+        }),
+        ItemEnum::Constant { type_, const_ } => {
+            v9::ItemEnum::Constant(convert_constant(convert_type(type_), const_))
+        }
+        ItemEnum::Static(static_) => v9::ItemEnum::Static(v9::Static {
+            type_: convert_type(&static_.type_),
+            mutable: static_.is_mutable,
+            expr: static_.expr.clone(),
+        }),
+        ItemEnum::ExternType => v9::ItemEnum::ForeignType,
+        ItemEnum::Macro(macro_) => v9::ItemEnum::Macro(macro_.clone()),
+        ItemEnum::ProcMacro(proc_macro) => v9::ItemEnum::ProcMacro(proc_macro.clone()),
+        ItemEnum::Primitive(primitive) => v9::ItemEnum::PrimitiveType(primitive.name.clone()),
+        ItemEnum::AssocConst { type_, value } => v9::ItemEnum::AssocConst {
+            type_: convert_type(type_),
+            default: value.clone(),
+        },
+        ItemEnum::AssocType { bounds, type_ } => v9::ItemEnum::AssocType {
+            bounds: convert_bounds(bounds),
+            default: type_.as_ref().map(convert_type),
+        },
+    }
+}
+
+fn convert_constant(type_: v9::Type, constant: &Constant) -> v9::Constant {
+    v9::Constant {
+        type_,
+        expr: constant.expr.clone(),
+        value: constant.value.clone(),
+        is_literal: constant.is_literal,
+    }
+}
+
+fn convert_sig(sig: &FunctionSignature) -> v9::FnDecl {
+    v9::FnDecl {
+        inputs: sig
+            .inputs
+            .iter()
+            .map(|(name, type_)| (name.clone(), convert_type(type_)))
+            .collect(),
+        output: sig.output.as_ref().map(convert_type),
+        c_variadic: sig.is_c_variadic,
+    }
+}
+
+/// Format 9 kept the ABI as its `Debug` rendering, like `"\"C\""`
+fn convert_header(header: &FunctionHeader) -> (HashSet<v9::Qualifiers>, String) {
+    let mut qualifiers = HashSet::new();
+    if header.is_const {
+        qualifiers.insert(v9::Qualifiers::Const);
+    }
+    if header.is_unsafe {
+        qualifiers.insert(v9::Qualifiers::Unsafe);
+    }
+    if header.is_async {
+        qualifiers.insert(v9::Qualifiers::Async);
+    }
+    let (name, unwind) = match &header.abi {
+        Abi::Rust => ("Rust", false),
+        Abi::C { unwind } => ("C", *unwind),
+        Abi::Cdecl { unwind } => ("cdecl", *unwind),
+        Abi::Stdcall { unwind } => ("stdcall", *unwind),
+        Abi::Fastcall { unwind } => ("fastcall", *unwind),
+        Abi::Aapcs { unwind } => ("aapcs", *unwind),
+        Abi::Win64 { unwind } => ("win64", *unwind),
+        Abi::SysV64 { unwind } => ("sysv64", *unwind),
+        Abi::System { unwind } => ("system", *unwind),
+        Abi::Other(abi) if abi.starts_with('"') => return (qualifiers, abi.clone()),
+        Abi::Other(abi) => (abi.as_str(), false),
+    };
+    let abi = if unwind {
+        format!("\"{}-unwind\"", name)
+    } else {
+        format!("\"{}\"", name)
+    };
+    (qualifiers, abi)
+}
+
+fn convert_generics(generics: &Generics) -> v9::Generics {
+    v9::Generics {
+        params: convert_generic_params(&generics.params),
+        where_predicates: generics
+            .where_predicates
+            .iter()
+            .map(|predicate| match predicate {
+                WherePredicate::BoundPredicate { type_, bounds } => {
+                    v9::WherePredicate::BoundPredicate {
+                        ty: convert_type(type_),
+                        bounds: convert_bounds(bounds),
+                    }
+                }
+                WherePredicate::LifetimePredicate { lifetime, outlives } => {
+                    v9::WherePredicate::RegionPredicate {
+                        lifetime: lifetime.clone(),
+                        bounds: outlives
+                            .iter()
+                            .cloned()
+                            .map(v9::GenericBound::Outlives)
+                            .collect(),
+                    }
+                }
+                WherePredicate::EqPredicate { lhs, rhs } => v9::WherePredicate::EqPredicate {
+                    lhs: convert_type(lhs),
+                    rhs: convert_term(rhs),
+                },
+            })
+            .collect(),
+    }
+}
+
+fn convert_generic_params(params: &[GenericParamDef]) -> Vec<v9::GenericParamDef> {
+    params
+        .iter()
+        .map(|param| v9::GenericParamDef {
+            name: param.name.clone(),
+            kind: match &param.kind {
+                GenericParamDefKind::Lifetime { outlives } => v9::GenericParamDefKind::Lifetime {
+                    outlives: outlives.clone(),
+                },
+                GenericParamDefKind::Type { bounds, default } => v9::GenericParamDefKind::Type {
+                    bounds: convert_bounds(bounds),
+                    default: default.as_ref().map(convert_type),
+                },
+                GenericParamDefKind::Const { type_, default } => v9::GenericParamDefKind::Const {
+                    ty: convert_type(type_),
+                    default: default.clone(),
+                },
+            },
+        })
+        .collect()
+}
+
+fn convert_bounds(bounds: &[GenericBound]) -> Vec<v9::GenericBound> {
+    bounds
+        .iter()
+        .map(|bound| match bound {
+            GenericBound::TraitBound {
+                trait_,
+                generic_params,
+                modifier,
+            } => v9::GenericBound::TraitBound {
+                trait_: convert_path(trait_),
+                generic_params: convert_generic_params(generic_params),
+                modifier: modifier.clone(),
+            },
+            GenericBound::Outlives(lifetime) => v9::GenericBound::Outlives(lifetime.clone()),
+            // Format 9 predates precise capturing, so only its rendering is kept
+            GenericBound::Use(args) => {
+                let args: Vec<_> =
+                    args.iter()
+                        .map(|arg| match arg {
+                            PreciseCapturingArg::Lifetime(name)
+                            | PreciseCapturingArg::Param(name) => name.as_str(),
+                        })
+                        .collect();
+                v9::GenericBound::Outlives(format!("use<{}>", args.join(", ")))
+            }
+        })
+        .collect()
+}
+
+fn convert_term(term: &Term) -> v9::Type {
+    match term {
+        Term::Type(type_) => convert_type(type_),
+        // Format 9 only bound types, so only the rendering is kept
+        Term::Constant(constant) => v9::Type::Generic(constant.expr.clone()),
+    }
+}
+
+fn convert_path(path: &Path) -> v9::Type {
+    v9::Type::ResolvedPath {
+        name: path.path.clone(),
+        id: convert_id(path.id),
+        args: path
+            .args
+            .as_deref()
+            .map(|args| Box::new(convert_generic_args(args))),
+        param_names: Vec::new(),
+    }
+}
+
+fn convert_generic_args(args: &GenericArgs) -> v9::GenericArgs {
+    match args {
+        GenericArgs::AngleBracketed { args, constraints } => v9::GenericArgs::AngleBracketed {
+            args: args
+                .iter()
+                .map(|arg| match arg {
+                    GenericArg::Lifetime(lifetime) => v9::GenericArg::Lifetime(lifetime.clone()),
+                    GenericArg::Type(type_) => v9::GenericArg::Type(convert_type(type_)),
+                    GenericArg::Const(constant) => {
+                        v9::GenericArg::Const(convert_constant(v9::Type::Infer, constant))
+                    }
+                    GenericArg::Infer => v9::GenericArg::Infer,
+                })
+                .collect(),
+            bindings: constraints
+                .iter()
+                .map(|constraint| v9::TypeBinding {
+                    name: constraint.name.clone(),
+                    binding: match &constraint.binding {
+                        AssocItemConstraintKind::Equality(term) => {
+                            v9::TypeBindingKind::Equality(convert_term(term))
+                        }
+                        AssocItemConstraintKind::Constraint(bounds) => {
+                            v9::TypeBindingKind::Constraint(convert_bounds(bounds))
+                        }
+                    },
+                })
+                .collect(),
+        },
+        GenericArgs::Parenthesized { inputs, output } => v9::GenericArgs::Parenthesized {
+            inputs: inputs.iter().map(convert_type).collect(),
+            output: output.as_ref().map(convert_type),
+        },
+        // Format 9 predates return type notation, so only its rendering, `(..)`, is kept
+        GenericArgs::ReturnTypeNotation => v9::GenericArgs::Parenthesized {
+            inputs: vec![v9::Type::Generic("..".to_owned())],
+            output: None,
+        },
+    }
+}
+
+fn convert_type(type_: &Type) -> v9::Type {
+    match type_ {
+        Type::ResolvedPath(path) => convert_path(path),
+        // Format 9 spelled `dyn A + B + 'a` as the path `A` with the bounds `B + 'a`
+        Type::DynTrait(dyn_trait) => {
+            let (first, rest) = dyn_trait
+                .traits
+                .split_first()
+                .expect("trait objects always have a trait");
+            let mut param_names: Vec<_> = rest
+                .iter()
+                .map(|poly| v9::GenericBound::TraitBound {
+                    trait_: convert_path(&poly.trait_),
+                    generic_params: convert_generic_params(&poly.generic_params),
+                    modifier: v9::TraitBoundModifier::None,
+                })
+                .collect();
+            param_names.extend(dyn_trait.lifetime.clone().map(v9::GenericBound::Outlives));
+            v9::Type::ResolvedPath {
+                name: first.trait_.path.clone(),
+                id: convert_id(first.trait_.id),
+                args: first
+                    .trait_
+                    .args
+                    .as_deref()
+                    .map(|args| Box::new(convert_generic_args(args))),
+                param_names,
+            }
+        }
+        Type::Generic(name) => v9::Type::Generic(name.clone()),
+        Type::Primitive(name) => v9::Type::Primitive(name.clone()),
+        Type::FunctionPointer(pointer) => {
+            let (header, abi) = convert_header(&pointer.header);
+            v9::Type::FunctionPointer(Box::new(v9::FunctionPointer {
+                decl: convert_sig(&pointer.sig),
+                generic_params: convert_generic_params(&pointer.generic_params),
+                header,
+                abi,
+            }))
+        }
+        Type::Tuple(types) => v9::Type::Tuple(types.iter().map(convert_type).collect()),
+        Type::Slice(type_) => v9::Type::Slice(Box::new(convert_type(type_))),
+        Type::Array { type_, len } => v9::Type::Array {
+            type_: Box::new(convert_type(type_)),
+            len: len.clone(),
+        },
+        // Pattern types are unstable and format 9 has no way to spell the pattern
+        Type::Pat { type_ } => convert_type(type_),
+        Type::ImplTrait(bounds) => v9::Type::ImplTrait(convert_bounds(bounds)),
+        Type::Infer => v9::Type::Infer,
+        Type::RawPointer { is_mutable, type_ } => v9::Type::RawPointer {
+            mutable: *is_mutable,
+            type_: Box::new(convert_type(type_)),
+        },
+        Type::BorrowedRef {
+            lifetime,
+            is_mutable,
+            type_,
+        } => v9::Type::BorrowedRef {
+            lifetime: lifetime.clone(),
+            mutable: *is_mutable,
+            type_: Box::new(convert_type(type_)),
+        },
+        Type::QualifiedPath {
+            name,
+            self_type,
+            trait_,
+        } => v9::Type::QualifiedPath {
+            name: name.clone(),
+            self_type: Box::new(convert_type(self_type)),
+            // Format 9 left the trait of shorthand paths, like `T::Item`, unnamed
+            trait_: Box::new(match trait_ {
+                Some(trait_) => convert_path(trait_),
+                None => v9::Type::Generic(String::new()),
+            }),
+        },
+    }
+}
@@ -0,0 +1,774 @@
+//! Render rustdoc items as Rust-like declarations
+
+/// Render the declaration for `raw_item`, if it has one, with a member per line like `rustfmt`
+pub(crate) fn render_item(
+    raw: &rustdoc_json_types_fork::Crate,
+    raw_item: &rustdoc_json_types_fork::Item,
+) -> Option<String> {
+    let name = raw_item.name.as_deref().unwrap_or("_");
+    let mut out = render_attrs(raw_item);
+    let vis = render_visibility(&raw_item.visibility);
+    match &raw_item.inner {
+        rustdoc_json_types_fork::ItemEnum::Module(_)
+        | rustdoc_json_types_fork::ItemEnum::Import(_)
+        | rustdoc_json_types_fork::ItemEnum::Impl(_) => return None,
+        rustdoc_json_types_fork::ItemEnum::ExternCrate { name, rename } => {
+            out.push_str(&format!("{}extern crate {}", vis, name));
+            if let Some(rename) = rename {
+                out.push_str(&format!(" as {}", rename));
+            }
+            out.push(';');
+        }
+        rustdoc_json_types_fork::ItemEnum::Union(union_) => {
+            out.push_str(&format!(
+                "{}union {}{}{}",
+                vis,
+                name,
+                render_generic_params(raw, &union_.generics.params),
+                render_where(raw, &union_.generics),
+            ));
+            out.push_str(&render_fields(raw, &union_.fields, union_.fields_stripped));
+        }
+        rustdoc_json_types_fork::ItemEnum::Struct(struct_) => {
+            out.push_str(&format!(
+                "{}struct {}{}",
+                vis,
+                name,
+                render_generic_params(raw, &struct_.generics.params),
+            ));
+            match struct_.struct_type {
+                rustdoc_json_types_fork::StructType::Plain => {
+                    out.push_str(&render_where(raw, &struct_.generics));
+                    out.push_str(&render_fields(
+                        raw,
+                        &struct_.fields,
+                        struct_.fields_stripped,
+                    ));
+                }
+                rustdoc_json_types_fork::StructType::Tuple => {
+                    let mut fields: Vec<_> = struct_
+                        .fields
+                        .iter()
+                        .filter_map(|raw_field_id| raw.index.get(raw_field_id))
+                        .filter_map(|raw_field| match &raw_field.inner {
+                            rustdoc_json_types_fork::ItemEnum::StructField(type_) => Some(format!(
+                                "{}{}",
+                                render_visibility(&raw_field.visibility),
+                                render_type(raw, type_)
+                            )),
+                            _ => None,
+                        })
+                        .collect();
+                    if struct_.fields_stripped {
+                        fields.push("/* private fields */".to_owned());
+                    }
+                    out.push_str(&format!(
+                        "({}){};",
+                        fields.join(", "),
+                        render_where(raw, &struct_.generics)
+                    ));
+                }
+                rustdoc_json_types_fork::StructType::Unit => {
+                    out.push_str(&render_where(raw, &struct_.generics));
+                    out.push(';');
+                }
+            }
+        }
+        rustdoc_json_types_fork::ItemEnum::StructField(type_) => {
+            out.push_str(&format!("{}{}: {}", vis, name, render_type(raw, type_)));
+        }
+        rustdoc_json_types_fork::ItemEnum::Enum(enum_) => {
+            out.push_str(&format!(
+                "{}enum {}{}{}",
+                vis,
+                name,
+                render_generic_params(raw, &enum_.generics.params),
+                render_where(raw, &enum_.generics),
+            ));
+            let mut variants: Vec<_> = enum_
+                .variants
+                .iter()
+                .filter_map(|raw_variant_id| raw.index.get(raw_variant_id))
+                .filter_map(|raw_variant| render_item(raw, raw_variant))
+                .map(|variant| format!("{},", variant))
+                .collect();
+            if enum_.variants_stripped {
+                variants.push(render_comment("some variants omitted"));
+            }
+            out.push_str(&render_block(&variants));
+        }
+        rustdoc_json_types_fork::ItemEnum::Variant(variant) => {
+            out.push_str(name);
+            match variant {
+                rustdoc_json_types_fork::Variant::Plain => {}
+                rustdoc_json_types_fork::Variant::Tuple(types) => {
+                    let types: Vec<_> = types.iter().map(|type_| render_type(raw, type_)).collect();
+                    out.push_str(&format!("({})", types.join(", ")));
+                }
+                rustdoc_json_types_fork::Variant::Struct(fields) => {
+                    let fields: Vec<_> = fields
+                        .iter()
+                        .filter_map(|raw_field_id| raw.index.get(raw_field_id))
+                        .filter_map(|raw_field| render_item(raw, raw_field))
+                        .collect();
+                    out.push_str(&format!(" {{ {} }}", fields.join(", ")));
+                }
+            }
+        }
+        rustdoc_json_types_fork::ItemEnum::Function(function) => {
+            out.push_str(&render_fn(
+                raw,
+                &vis,
+                name,
+                &function.header,
+                &function.abi,
+                &function.generics,
+                &function.decl,
+            ));
+        }
+        rustdoc_json_types_fork::ItemEnum::Method(method) => {
+            out.push_str(&render_fn(
+                raw,
+                &vis,
+                name,
+                &method.header,
+                &method.abi,
+                &method.generics,
+                &method.decl,
+            ));
+        }
+        rustdoc_json_types_fork::ItemEnum::Trait(trait_) => {
+            let unsafe_ = if trait_.is_unsafe { "unsafe " } else { "" };
+            let auto = if trait_.is_auto { "auto " } else { "" };
+            out.push_str(&format!(
+                "{}{}{}trait {}{}{}{}",
+                vis,
+                unsafe_,
+                auto,
+                name,
+                render_generic_params(raw, &trait_.generics.params),
+                render_supertraits(raw, &trait_.bounds),
+                render_where(raw, &trait_.generics),
+            ));
+            let items: Vec<_> = trait_
+                .items
+                .iter()
+                .filter_map(|raw_member_id| raw.index.get(raw_member_id))
+                .filter_map(|raw_member| {
+                    let member = render_item(raw, raw_member)?;
+                    match &raw_member.inner {
+                        rustdoc_json_types_fork::ItemEnum::Method(method) if method.has_body => {
+                            Some(format!("{} {{ ... }}", member))
+                        }
+                        rustdoc_json_types_fork::ItemEnum::Method(_) => {
+                            Some(format!("{};", member))
+                        }
+                        _ => Some(member),
+                    }
+                })
+                .collect();
+            out.push_str(&render_block(&items));
+        }
+        rustdoc_json_types_fork::ItemEnum::TraitAlias(alias) => {
+            out.push_str(&format!(
+                "{}trait {}{} = {}{};",
+                vis,
+                name,
+                render_generic_params(raw, &alias.generics.params),
+                render_bounds(raw, &alias.params),
+                render_where(raw, &alias.generics),
+            ));
+        }
+        rustdoc_json_types_fork::ItemEnum::Typedef(typedef) => {
+            out.push_str(&format!(
+                "{}type {}{}{} = {};",
+                vis,
+                name,
+                render_generic_params(raw, &typedef.generics.params),
+                render_where(raw, &typedef.generics),
+                render_type(raw, &typedef.type_),
+            ));
+        }
+        rustdoc_json_types_fork::ItemEnum::OpaqueTy(opaque) => {
+            out.push_str(&format!(
+                "{}type {}{}{} = impl {};",
+                vis,
+                name,
+                render_generic_params(raw, &opaque.generics.params),
+                render_where(raw, &opaque.generics),
+                render_bounds(raw, &opaque.bounds),
+            ));
+        }
+        rustdoc_json_types_fork::ItemEnum::Constant(constant) => {
+            out.push_str(&format!(
+                "{}const {}: {} = {};",
+                vis,
+                name,
+                render_type(raw, &constant.type_),
+                constant.expr
+            ));
+        }
+        rustdoc_json_types_fork::ItemEnum::Static(static_) => {
+            let mut_ = if static_.mutable { "mut " } else { "" };
+            out.push_str(&format!(
+                "{}static {}{}: {};",
+                vis,
+                mut_,
+                name,
+                render_type(raw, &static_.type_)
+            ));
+        }
+        rustdoc_json_types_fork::ItemEnum::ForeignType => {
+            out.push_str(&format!("{}type {};", vis, name));
+        }
+        rustdoc_json_types_fork::ItemEnum::Macro(macro_) => {
+            out.push_str(macro_);
+        }
+        rustdoc_json_types_fork::ItemEnum::ProcMacro(proc_macro) => match proc_macro.kind {
+            rustdoc_json_types_fork::MacroKind::Bang => {
+                out.push_str(&format!("{}!() {{ /* proc-macro */ }}", name));
+            }
+            rustdoc_json_types_fork::MacroKind::Attr => {
+                out.push_str(&format!("#[{}]", name));
+            }
+            rustdoc_json_types_fork::MacroKind::Derive => {
+                out.push_str(&format!("#[derive({})]", name));
+                if !proc_macro.helpers.is_empty() {
+                    let helpers: Vec<_> = proc_macro
+                        .helpers
+                        .iter()
+                        .map(|helper| format!("#[{}]", helper))
+                        .collect();
+                    let comment = format!("helper attributes: {}", helpers.join(", "));
+                    out.push('\n');
+                    out.push_str(&render_comment(&comment));
+                }
+            }
+        },
+        rustdoc_json_types_fork::ItemEnum::PrimitiveType(primitive) => {
+            out.push_str(primitive);
+        }
+        rustdoc_json_types_fork::ItemEnum::AssocConst { type_, default } => {
+            out.push_str(&format!("const {}: {}", name, render_type(raw, type_)));
+            if let Some(default) = default {
+                out.push_str(&format!(" = {}", default));
+            }
+            out.push(';');
+        }
+        rustdoc_json_types_fork::ItemEnum::AssocType { bounds, default } => {
+            out.push_str(&format!("type {}", name));
+            if !bounds.is_empty() {
+                out.push_str(&format!(": {}", render_bounds(raw, bounds)));
+            }
+            if let Some(default) = default {
+                out.push_str(&format!(" = {}", render_type(raw, default)));
+            }
+            out.push(';');
+        }
+    }
+    Some(out)
+}
+
+/// Render `path` on one line, naming it by its full path
+///
+/// Re-exports name what they re-export in a trailing comment.  Paths without a signature, like
+/// variants, are rendered as the bare path.
+pub fn render_declaration(path: &crate::Path) -> String {
+    let signature = match (path.kind, path.signature.as_deref()) {
+        (crate::PathKind::Module, _) => return format!("pub mod {}", path.path),
+        (_, None) => return path.path.clone(),
+        (_, Some(signature)) => signature,
+    };
+    if path.kind == crate::PathKind::Import {
+        let target = signature
+            .trim_start_matches("pub use ")
+            .trim_end_matches(';');
+        let target = target.split(" as ").next().unwrap_or(target);
+        return format!("pub use {}; // {}", path.path, target);
+    }
+
+    // Collapse the members of blocks onto the item's line
+    let mut line = String::new();
+    // The name is only replaced after attributes, which can mention it, like `#[repr(C)]`
+    let mut is_named = matches!(
+        path.kind,
+        crate::PathKind::Primitive | crate::PathKind::ProcAttribute | crate::PathKind::ProcDerive
+    );
+    for part in signature.lines().map(|line| line.trim()) {
+        if part.is_empty() {
+            continue;
+        }
+        let part = match part.strip_prefix("// ") {
+            Some(comment) => format!("/* {} */", comment),
+            None if !is_named && !part.starts_with("#[") => {
+                is_named = true;
+                let name = path.path.rsplit("::").next().unwrap_or(&path.path);
+                replace_name(part, name, &path.path)
+            }
+            None => part.to_owned(),
+        };
+        // Like a list of fields, drop the trailing comma
+        if part == "}" && path.kind != crate::PathKind::Macro && line.ends_with(',') {
+            line.pop();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&part);
+    }
+    line
+}
+
+/// Replace the first use of `name` as an identifier in `text` with `path`
+fn replace_name(text: &str, name: &str, path: &str) -> String {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    for (start, _) in text.match_indices(name) {
+        let end = start + name.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        if !before.map_or(false, is_ident) && !after.map_or(false, is_ident) {
+            return format!("{}{}{}", &text[..start], path, &text[end..]);
+        }
+    }
+    text.to_owned()
+}
+
+/// Attributes that affect the API
+fn render_attrs(raw_item: &rustdoc_json_types_fork::Item) -> String {
+    raw_item
+        .attrs
+        .iter()
+        .filter(|attr| attr.starts_with("#[non_exhaustive") || attr.starts_with("#[repr("))
+        .map(|attr| format!("{}\n", attr))
+        .collect()
+}
+
+fn render_comment(comment: &str) -> String {
+    format!("// {}", comment)
+}
+
+fn render_visibility(visibility: &rustdoc_json_types_fork::Visibility) -> String {
+    match visibility {
+        rustdoc_json_types_fork::Visibility::Public => "pub ".to_owned(),
+        rustdoc_json_types_fork::Visibility::Default => "".to_owned(),
+        rustdoc_json_types_fork::Visibility::Crate => "pub(crate) ".to_owned(),
+        rustdoc_json_types_fork::Visibility::Restricted { path, .. } => {
+            format!("pub(in {}) ", path)
+        }
+    }
+}
+
+fn render_block(lines: &[String]) -> String {
+    if lines.is_empty() {
+        " {}".to_owned()
+    } else {
+        let mut out = " {\n".to_owned();
+        for line in lines {
+            for line in line.lines() {
+                out.push_str(&format!("    {}\n", line));
+            }
+        }
+        out.push('}');
+        out
+    }
+}
+
+fn render_fields(
+    raw: &rustdoc_json_types_fork::Crate,
+    raw_field_ids: &[rustdoc_json_types_fork::Id],
+    stripped: bool,
+) -> String {
+    let mut fields: Vec<_> = raw_field_ids
+        .iter()
+        .filter_map(|raw_field_id| raw.index.get(raw_field_id))
+        .filter_map(|raw_field| render_item(raw, raw_field))
+        .map(|field| format!("{},", field))
+        .collect();
+    if stripped {
+        fields.push(render_comment("some fields omitted"));
+    }
+    render_block(&fields)
+}
+
+fn render_fn(
+    raw: &rustdoc_json_types_fork::Crate,
+    vis: &str,
+    name: &str,
+    header: &std::collections::HashSet<rustdoc_json_types_fork::Qualifiers>,
+    abi: &str,
+    generics: &rustdoc_json_types_fork::Generics,
+    decl: &rustdoc_json_types_fork::FnDecl,
+) -> String {
+    format!(
+        "{}{}fn {}{}{}{}",
+        vis,
+        render_fn_header(header, abi),
+        name,
+        render_generic_params(raw, &generics.params),
+        render_decl(raw, decl),
+        render_where(raw, generics),
+    )
+}
+
+fn render_fn_header(
+    header: &std::collections::HashSet<rustdoc_json_types_fork::Qualifiers>,
+    abi: &str,
+) -> String {
+    let mut out = String::new();
+    if header.contains(&rustdoc_json_types_fork::Qualifiers::Const) {
+        out.push_str("const ");
+    }
+    if header.contains(&rustdoc_json_types_fork::Qualifiers::Async) {
+        out.push_str("async ");
+    }
+    if header.contains(&rustdoc_json_types_fork::Qualifiers::Unsafe) {
+        out.push_str("unsafe ");
+    }
+    if abi != "\"Rust\"" {
+        out.push_str(&format!("extern {} ", abi));
+    }
+    out
+}
+
+fn render_decl(
+    raw: &rustdoc_json_types_fork::Crate,
+    decl: &rustdoc_json_types_fork::FnDecl,
+) -> String {
+    let mut inputs: Vec<_> = decl
+        .inputs
+        .iter()
+        .map(|(name, type_)| render_input(raw, name, type_))
+        .collect();
+    if decl.c_variadic {
+        inputs.push("...".to_owned());
+    }
+    let mut out = format!("({})", inputs.join(", "));
+    if let Some(output) = decl.output.as_ref() {
+        out.push_str(&format!(" -> {}", render_type(raw, output)));
+    }
+    out
+}
+
+fn render_input(
+    raw: &rustdoc_json_types_fork::Crate,
+    name: &str,
+    type_: &rustdoc_json_types_fork::Type,
+) -> String {
+    if name == "self" {
+        match type_ {
+            rustdoc_json_types_fork::Type::Generic(generic) if generic == "Self" => {
+                return "self".to_owned();
+            }
+            rustdoc_json_types_fork::Type::BorrowedRef {
+                lifetime,
+                mutable,
+                type_,
+            } if matches!(type_.as_ref(), rustdoc_json_types_fork::Type::Generic(generic) if generic == "Self") =>
+            {
+                return format!("{}self", render_ref(lifetime.as_deref(), *mutable));
+            }
+            _ => {}
+        }
+    }
+    format!("{}: {}", name, render_type(raw, type_))
+}
+
+fn render_ref(lifetime: Option<&str>, mutable: bool) -> String {
+    let mut out = "&".to_owned();
+    if let Some(lifetime) = lifetime {
+        out.push_str(lifetime);
+        out.push(' ');
+    }
+    if mutable {
+        out.push_str("mut ");
+    }
+    out
+}
+
+fn render_generic_params(
+    raw: &rustdoc_json_types_fork::Crate,
+    params: &[rustdoc_json_types_fork::GenericParamDef],
+) -> String {
+    let params: Vec<_> = params
+        .iter()
+        // `impl Trait` arguments show up as synthetic parameters
+        .filter(|param| !param.name.starts_with("impl "))
+        .map(|param| render_generic_param(raw, param))
+        .collect();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn render_generic_param(
+    raw: &rustdoc_json_types_fork::Crate,
+    param: &rustdoc_json_types_fork::GenericParamDef,
+) -> String {
+    match &param.kind {
+        rustdoc_json_types_fork::GenericParamDefKind::Lifetime { outlives } => {
+            if outlives.is_empty() {
+                param.name.clone()
+            } else {
+                format!("{}: {}", param.name, outlives.join(" + "))
+            }
+        }
+        rustdoc_json_types_fork::GenericParamDefKind::Type { bounds, default } => {
+            let mut out = param.name.clone();
+            if !bounds.is_empty() {
+                out.push_str(&format!(": {}", render_bounds(raw, bounds)));
+            }
+            if let Some(default) = default {
+                out.push_str(&format!(" = {}", render_type(raw, default)));
+            }
+            out
+        }
+        rustdoc_json_types_fork::GenericParamDefKind::Const { ty, default } => {
+            let mut out = format!("const {}: {}", param.name, render_type(raw, ty));
+            if let Some(default) = default {
+                out.push_str(&format!(" = {}", default));
+            }
+            out
+        }
+    }
+}
+
+fn render_where(
+    raw: &rustdoc_json_types_fork::Crate,
+    generics: &rustdoc_json_types_fork::Generics,
+) -> String {
+    let predicates: Vec<_> = generics
+        .where_predicates
+        .iter()
+        .map(|predicate| match predicate {
+            rustdoc_json_types_fork::WherePredicate::BoundPredicate { ty, bounds } => {
+                format!("{}: {}", render_type(raw, ty), render_bounds(raw, bounds))
+            }
+            rustdoc_json_types_fork::WherePredicate::RegionPredicate { lifetime, bounds } => {
+                format!("{}: {}", lifetime, render_bounds(raw, bounds))
+            }
+            rustdoc_json_types_fork::WherePredicate::EqPredicate { lhs, rhs } => {
+                format!("{} = {}", render_type(raw, lhs), render_type(raw, rhs))
+            }
+        })
+        .collect();
+    if predicates.is_empty() {
+        String::new()
+    } else {
+        format!(" where {}", predicates.join(", "))
+    }
+}
+
+fn render_supertraits(
+    raw: &rustdoc_json_types_fork::Crate,
+    bounds: &[rustdoc_json_types_fork::GenericBound],
+) -> String {
+    if bounds.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", render_bounds(raw, bounds))
+    }
+}
+
+fn render_bounds(
+    raw: &rustdoc_json_types_fork::Crate,
+    bounds: &[rustdoc_json_types_fork::GenericBound],
+) -> String {
+    bounds
+        .iter()
+        .map(|bound| match bound {
+            rustdoc_json_types_fork::GenericBound::TraitBound {
+                trait_,
+                generic_params,
+                modifier,
+            } => {
+                let mut out = render_hrtb(raw, generic_params);
+                match modifier {
+                    rustdoc_json_types_fork::TraitBoundModifier::None => {}
+                    rustdoc_json_types_fork::TraitBoundModifier::Maybe => out.push('?'),
+                    rustdoc_json_types_fork::TraitBoundModifier::MaybeConst => {
+                        out.push_str("~const ")
+                    }
+                }
+                out.push_str(&render_path(raw, trait_));
+                out
+            }
+            rustdoc_json_types_fork::GenericBound::Outlives(lifetime) => lifetime.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Higher-ranked trait bounds, like `for<'a>`
+fn render_hrtb(
+    raw: &rustdoc_json_types_fork::Crate,
+    params: &[rustdoc_json_types_fork::GenericParamDef],
+) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        let params: Vec<_> = params
+            .iter()
+            .map(|param| render_generic_param(raw, param))
+            .collect();
+        format!("for<{}> ", params.join(", "))
+    }
+}
+
+fn render_type(
+    raw: &rustdoc_json_types_fork::Crate,
+    type_: &rustdoc_json_types_fork::Type,
+) -> String {
+    match type_ {
+        rustdoc_json_types_fork::Type::ResolvedPath {
+            id, param_names, ..
+        } => {
+            let path = render_path(raw, type_);
+            // rustdoc only marks trait objects that have extra bounds, so check what the path is
+            if !param_names.is_empty() {
+                format!("dyn {} + {}", path, render_bounds(raw, param_names))
+            } else if is_trait(raw, id) {
+                format!("dyn {}", path)
+            } else {
+                path
+            }
+        }
+        rustdoc_json_types_fork::Type::Generic(name)
+        | rustdoc_json_types_fork::Type::Primitive(name) => name.clone(),
+        rustdoc_json_types_fork::Type::FunctionPointer(pointer) => format!(
+            "{}{}fn{}",
+            render_hrtb(raw, &pointer.generic_params),
+            render_fn_header(&pointer.header, &pointer.abi),
+            render_decl(raw, &pointer.decl)
+        ),
+        rustdoc_json_types_fork::Type::Tuple(types) => match types.as_slice() {
+            [type_] => format!("({},)", render_type(raw, type_)),
+            types => format!(
+                "({})",
+                types
+                    .iter()
+                    .map(|type_| render_type(raw, type_))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        },
+        rustdoc_json_types_fork::Type::Slice(type_) => format!("[{}]", render_type(raw, type_)),
+        rustdoc_json_types_fork::Type::Array { type_, len } => {
+            format!("[{}; {}]", render_type(raw, type_), len)
+        }
+        rustdoc_json_types_fork::Type::ImplTrait(bounds) => {
+            format!("impl {}", render_bounds(raw, bounds))
+        }
+        rustdoc_json_types_fork::Type::Infer => "_".to_owned(),
+        rustdoc_json_types_fork::Type::RawPointer { mutable, type_ } => {
+            let mut_ = if *mutable { "mut" } else { "const" };
+            format!("*{} {}", mut_, render_pointee(raw, type_))
+        }
+        rustdoc_json_types_fork::Type::BorrowedRef {
+            lifetime,
+            mutable,
+            type_,
+        } => format!(
+            "{}{}",
+            render_ref(lifetime.as_deref(), *mutable),
+            render_pointee(raw, type_)
+        ),
+        rustdoc_json_types_fork::Type::QualifiedPath {
+            name,
+            self_type,
+            trait_,
+        } => match render_path(raw, trait_).as_str() {
+            // Shorthand, like `T::Item`
+            "" => format!("{}::{}", render_type(raw, self_type), name),
+            trait_ => format!("<{} as {}>::{}", render_type(raw, self_type), trait_, name),
+        },
+    }
+}
+
+/// Render a path without treating traits as trait objects, like for bounds
+fn render_path(
+    raw: &rustdoc_json_types_fork::Crate,
+    type_: &rustdoc_json_types_fork::Type,
+) -> String {
+    match type_ {
+        rustdoc_json_types_fork::Type::ResolvedPath { name, args, .. } => {
+            let mut out = name.clone();
+            if let Some(args) = args.as_deref() {
+                out.push_str(&render_generic_args(raw, args));
+            }
+            out
+        }
+        _ => render_type(raw, type_),
+    }
+}
+
+/// Render what a reference or pointer points to, grouping trait objects with several bounds
+fn render_pointee(
+    raw: &rustdoc_json_types_fork::Crate,
+    type_: &rustdoc_json_types_fork::Type,
+) -> String {
+    match type_ {
+        rustdoc_json_types_fork::Type::ResolvedPath { param_names, .. }
+            if !param_names.is_empty() =>
+        {
+            format!("({})", render_type(raw, type_))
+        }
+        _ => render_type(raw, type_),
+    }
+}
+
+fn is_trait(raw: &rustdoc_json_types_fork::Crate, id: &rustdoc_json_types_fork::Id) -> bool {
+    match raw.paths.get(id) {
+        Some(summary) => matches!(
+            summary.kind,
+            rustdoc_json_types_fork::ItemKind::Trait
+                | rustdoc_json_types_fork::ItemKind::TraitAlias
+        ),
+        None => matches!(
+            raw.index.get(id).map(|item| &item.inner),
+            Some(rustdoc_json_types_fork::ItemEnum::Trait(_))
+                | Some(rustdoc_json_types_fork::ItemEnum::TraitAlias(_))
+        ),
+    }
+}
+
+fn render_generic_args(
+    raw: &rustdoc_json_types_fork::Crate,
+    args: &rustdoc_json_types_fork::GenericArgs,
+) -> String {
+    match args {
+        rustdoc_json_types_fork::GenericArgs::AngleBracketed { args, bindings } => {
+            let mut rendered: Vec<_> = args
+                .iter()
+                .map(|arg| match arg {
+                    rustdoc_json_types_fork::GenericArg::Lifetime(lifetime) => lifetime.clone(),
+                    rustdoc_json_types_fork::GenericArg::Type(type_) => render_type(raw, type_),
+                    rustdoc_json_types_fork::GenericArg::Const(constant) => constant.expr.clone(),
+                    rustdoc_json_types_fork::GenericArg::Infer => "_".to_owned(),
+                })
+                .collect();
+            rendered.extend(bindings.iter().map(|binding| match &binding.binding {
+                rustdoc_json_types_fork::TypeBindingKind::Equality(type_) => {
+                    format!("{} = {}", binding.name, render_type(raw, type_))
+                }
+                rustdoc_json_types_fork::TypeBindingKind::Constraint(bounds) => {
+                    format!("{}: {}", binding.name, render_bounds(raw, bounds))
+                }
+            }));
+            if rendered.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", rendered.join(", "))
+            }
+        }
+        rustdoc_json_types_fork::GenericArgs::Parenthesized { inputs, output } => {
+            let inputs: Vec<_> = inputs.iter().map(|type_| render_type(raw, type_)).collect();
+            let mut out = format!("({})", inputs.join(", "));
+            if let Some(output) = output {
+                out.push_str(&format!(" -> {}", render_type(raw, output)));
+            }
+            out
+        }
+    }
+}
@@ -0,0 +1,157 @@
+/// A git revision checked out as a detached worktree, including its submodules
+///
+/// The worktree is reused between runs and can be shared by every package being compared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Worktree {
+    path: std::path::PathBuf,
+    rev: String,
+    commit_id: String,
+    tree_id: String,
+}
+
+impl Worktree {
+    /// Check out `rev` from the repository containing `source` within `target_directory`
+    pub fn checkout(
+        source: &std::path::Path,
+        rev: &str,
+        target_directory: &std::path::Path,
+    ) -> Result<Self, crate::Error> {
+        let git_err = |e: git2::Error| {
+            crate::Error::new(
+                crate::ErrorKind::Git,
+                format!("Failed to checkout {}: {}", rev, e.message()),
+            )
+            .set_source(e)
+        };
+
+        let repo = git2::Repository::discover(source).map_err(git_err)?;
+        let commit = repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(git_err)?;
+        let commit_id = commit.id().to_string();
+        let tree_id = commit.tree_id().to_string();
+        let repo_dir = repo.workdir().unwrap_or_else(|| repo.path()).to_owned();
+
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let path = cwd
+            .join(target_directory)
+            .join("worktrees")
+            .join(&commit_id[..12]);
+        if is_worktree(&path) {
+            git(
+                &path,
+                &["checkout", "--quiet", "--force", "--detach", &commit_id],
+                rev,
+            )?;
+        } else {
+            // Forget worktrees whose directories were deleted, like from `cargo clean`
+            git(&repo_dir, &["worktree", "prune"], rev)?;
+            let path_arg = path.to_string_lossy();
+            git(
+                &repo_dir,
+                &[
+                    "worktree", "add", "--force", "--detach", &path_arg, &commit_id,
+                ],
+                rev,
+            )?;
+        }
+        git(
+            &path,
+            &["submodule", "update", "--init", "--recursive", "--force"],
+            rev,
+        )?;
+
+        Ok(Self {
+            path,
+            rev: rev.to_owned(),
+            commit_id,
+            tree_id,
+        })
+    }
+
+    /// Root of the checked out worktree
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// The revision as requested by the user
+    pub fn rev(&self) -> &str {
+        &self.rev
+    }
+
+    /// The commit `rev` resolved to
+    pub fn commit_id(&self) -> &str {
+        &self.commit_id
+    }
+
+    /// The tree of `commit_id`, identifying its content
+    pub fn tree_id(&self) -> &str {
+        &self.tree_id
+    }
+
+    /// Find the manifest of the workspace member named `package`
+    pub fn manifest_path(&self, package: &str) -> Result<std::path::PathBuf, crate::Error> {
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .current_dir(&self.path)
+            .no_deps()
+            .exec()
+            .map_err(|e| {
+                crate::Error::new(
+                    crate::ErrorKind::InvalidManifest,
+                    format!("Failed when reading {}: {}", self.path.display(), e),
+                )
+                .set_source(e)
+            })?;
+        metadata
+            .packages
+            .iter()
+            .filter(|p| metadata.workspace_members.contains(&p.id))
+            .filter(|p| p.name == package)
+            .map(|p| p.manifest_path.as_std_path().to_owned())
+            .next()
+            .ok_or_else(|| {
+                crate::Error::new(
+                    crate::ErrorKind::Unknown,
+                    format!(
+                        "Could not find {} at {} ({})",
+                        package,
+                        self.rev,
+                        self.path.display()
+                    ),
+                )
+            })
+    }
+}
+
+fn is_worktree(path: &std::path::Path) -> bool {
+    // Worktrees have a `.git` file pointing back to the repository
+    path.join(".git").is_file()
+}
+
+fn git(dir: &std::path::Path, args: &[&str], rev: &str) -> Result<(), crate::Error> {
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| {
+            crate::Error::new(
+                crate::ErrorKind::Git,
+                format!("Failed to checkout {}: could not run git: {}", rev, e),
+            )
+            .set_source(e)
+        })?;
+    if !output.status.success() {
+        return Err(crate::Error::new(
+            crate::ErrorKind::Git,
+            format!(
+                "Failed to checkout {}: `git {}` failed: {}",
+                rev,
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ));
+    }
+    Ok(())
+}
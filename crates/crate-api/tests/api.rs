@@ -0,0 +1,73 @@
+#[test]
+fn retain_paths_reindexes() {
+    let mut api = crate_api::Api::new();
+    let root_id = api
+        .paths
+        .push(crate_api::Path::new(crate_api::PathKind::Module, "mycrate"));
+    let struct_id = api.paths.push(crate_api::Path::new(
+        crate_api::PathKind::Struct,
+        "mycrate::Abc",
+    ));
+    let module_id = api.paths.push(crate_api::Path::new(
+        crate_api::PathKind::Module,
+        "mycrate::a",
+    ));
+    let fn_id = api.paths.push(crate_api::Path::new(
+        crate_api::PathKind::Function,
+        "mycrate::a::bcd",
+    ));
+    api.paths.get_mut(root_id).unwrap().children = vec![struct_id, module_id];
+    api.paths.get_mut(module_id).unwrap().children = vec![fn_id];
+    api.root_id = Some(root_id);
+    let mut dep = crate_api::Crate::new("dep");
+    dep.exposed_by = vec![struct_id, fn_id];
+    let dep_id = api.crates.push(dep);
+
+    api.retain_paths(|path| path.kind == crate_api::PathKind::Function);
+
+    let name = |path_id: &crate_api::PathId| api.paths.get(*path_id).unwrap().path.as_str();
+    // The function and the modules leading to it
+    assert_eq!(api.paths.len(), 3);
+    let root = api.paths.get(api.root_id.unwrap()).unwrap();
+    assert_eq!(root.path, "mycrate");
+    assert_eq!(
+        root.children.iter().map(name).collect::<Vec<_>>(),
+        ["mycrate::a"]
+    );
+    let module = api.paths.get(root.children[0]).unwrap();
+    assert_eq!(
+        module.children.iter().map(name).collect::<Vec<_>>(),
+        ["mycrate::a::bcd"]
+    );
+    assert_eq!(
+        api.crates
+            .get(dep_id)
+            .unwrap()
+            .exposed_by
+            .iter()
+            .map(name)
+            .collect::<Vec<_>>(),
+        ["mycrate::a::bcd"]
+    );
+}
+
+#[test]
+fn retain_paths_keeps_root() {
+    let mut api = crate_api::Api::new();
+    let root_id = api
+        .paths
+        .push(crate_api::Path::new(crate_api::PathKind::Module, "mycrate"));
+    let struct_id = api.paths.push(crate_api::Path::new(
+        crate_api::PathKind::Struct,
+        "mycrate::Abc",
+    ));
+    api.paths.get_mut(root_id).unwrap().children = vec![struct_id];
+    api.root_id = Some(root_id);
+
+    api.retain_paths(|_| false);
+
+    assert_eq!(api.paths.len(), 1);
+    let root = api.paths.get(api.root_id.unwrap()).unwrap();
+    assert_eq!(root.path, "mycrate");
+    assert!(root.children.is_empty());
+}
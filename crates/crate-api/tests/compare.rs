@@ -0,0 +1,46 @@
+fn package(dir: &std::path::Path, lib: &str) -> std::path::PathBuf {
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"shared\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("src/lib.rs"), lib).unwrap();
+    dir.join("Cargo.toml")
+}
+
+/// With a shared target directory, the sides would otherwise overwrite each other's
+/// `doc/shared.json`
+#[test]
+fn sides_build_apart_in_cargo_target_dir() {
+    let root = tempfile::tempdir().unwrap();
+    let target_dir = root.path().join("target");
+    std::env::set_var("CARGO_TARGET_DIR", &target_dir);
+
+    let before = package(&root.path().join("before"), "pub fn kept() {}\n");
+    let after = package(
+        &root.path().join("after"),
+        "pub fn kept() {}\npub fn added() {}\n",
+    );
+    let comparison = crate_api::ComparisonBuilder::new()
+        .rustdoc(crate_api::RustDocBuilder::new().silence(true))
+        .compare_paths(&before, &after)
+        .unwrap();
+
+    assert!(target_dir.join("crate-api/before/doc/shared.json").exists());
+    assert!(target_dir.join("crate-api/after/doc/shared.json").exists());
+    let paths = |api: &crate_api::Api| {
+        let mut paths: Vec<_> = api
+            .paths
+            .iter()
+            .map(|(_, path)| path.path.clone())
+            .collect();
+        paths.sort();
+        paths
+    };
+    assert_eq!(paths(&comparison.before), ["shared", "shared::kept"]);
+    assert_eq!(
+        paths(&comparison.after),
+        ["shared", "shared::added", "shared::kept"]
+    );
+}
@@ -0,0 +1,101 @@
+fn metadata(manifest_path: &str) -> (cargo_metadata::Metadata, cargo_metadata::Package) {
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .manifest_path(manifest_path)
+        .exec()
+        .unwrap();
+    let root_id = metadata.resolve.as_ref().unwrap().root.clone().unwrap();
+    let pkg = metadata
+        .packages
+        .iter()
+        .find(|p| p.id == root_id)
+        .unwrap()
+        .clone();
+    (metadata, pkg)
+}
+
+#[test]
+fn renamed_dependency_resolves_separately() {
+    let (metadata, pkg) = metadata("../../fixtures/dep_versions/lib/Cargo.toml");
+    let manifest = crate_api::manifest::Manifest::from_metadata(&metadata, &pkg);
+
+    let mut resolved: Vec<_> = manifest
+        .dependencies
+        .iter()
+        .map(|d| {
+            (
+                d.rename.as_deref().unwrap_or(&d.name).to_owned(),
+                d.resolved.as_ref().map(|v| v.to_string()),
+                d.lib_name.clone(),
+            )
+        })
+        .collect();
+    resolved.sort();
+    assert_eq!(
+        resolved,
+        vec![
+            (
+                "dep2".to_owned(),
+                Some("2.0.0".to_owned()),
+                Some("dep_upgrade_dep".to_owned())
+            ),
+            (
+                "dep_upgrade_dep".to_owned(),
+                Some("1.0.0".to_owned()),
+                Some("dep_upgrade_dep".to_owned())
+            ),
+        ]
+    );
+}
+
+fn api_with_versions(extern_names: &[&[&str]]) -> (crate_api::Api, Vec<crate_api::CrateId>) {
+    let mut api = crate_api::Api::new();
+    let crate_ids = extern_names
+        .iter()
+        .map(|extern_names| {
+            let mut crate_ = crate_api::Crate::new("dep_upgrade_dep");
+            crate_.extern_names = extern_names.iter().map(|n| (*n).to_owned()).collect();
+            api.crates.push(crate_)
+        })
+        .collect();
+    (api, crate_ids)
+}
+
+fn resolved(api: &crate_api::Api, crate_id: crate_api::CrateId) -> Option<String> {
+    api.crates
+        .get(crate_id)
+        .unwrap()
+        .resolved
+        .as_ref()
+        .map(|v| v.to_string())
+}
+
+#[test]
+fn multiple_versions_matched_by_extern_name() {
+    let (metadata, pkg) = metadata("../../fixtures/dep_versions/lib/Cargo.toml");
+    let (mut api, crate_ids) = api_with_versions(&[&["dep2"], &["dep_upgrade_dep"]]);
+    crate_api::manifest::Manifest::from_metadata(&metadata, &pkg).into_api(&mut api);
+
+    assert_eq!(resolved(&api, crate_ids[0]), Some("2.0.0".to_owned()));
+    assert_eq!(resolved(&api, crate_ids[1]), Some("1.0.0".to_owned()));
+}
+
+#[test]
+fn multiple_versions_matched_by_elimination() {
+    let (metadata, pkg) = metadata("../../fixtures/dep_versions/lib/Cargo.toml");
+    // Only referred to through imported names
+    let (mut api, crate_ids) = api_with_versions(&[&[], &["dep2"]]);
+    crate_api::manifest::Manifest::from_metadata(&metadata, &pkg).into_api(&mut api);
+
+    assert_eq!(resolved(&api, crate_ids[0]), Some("1.0.0".to_owned()));
+    assert_eq!(resolved(&api, crate_ids[1]), Some("2.0.0".to_owned()));
+}
+
+#[test]
+fn multiple_versions_ambiguous() {
+    let (metadata, pkg) = metadata("../../fixtures/dep_versions/lib/Cargo.toml");
+    let (mut api, crate_ids) = api_with_versions(&[&[], &[]]);
+    crate_api::manifest::Manifest::from_metadata(&metadata, &pkg).into_api(&mut api);
+
+    assert_eq!(resolved(&api, crate_ids[0]), None);
+    assert_eq!(resolved(&api, crate_ids[1]), None);
+}
@@ -16,12 +16,23 @@ fn main() {
         move |input_path| {
             let age_dir = input_path.parent().unwrap();
             let case_dir = age_dir.parent().unwrap();
+            // Like `-v57` in `rustdoc-raw-v57.json`
+            let format = input_path
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .strip_prefix("rustdoc-raw")
+                .unwrap()
+                .strip_suffix(".json")
+                .unwrap();
             let name = format!(
-                "{}_{}",
+                "{}_{}{}",
                 case_dir.file_name().unwrap().to_str().unwrap(),
-                age_dir.file_name().unwrap().to_str().unwrap()
+                age_dir.file_name().unwrap().to_str().unwrap(),
+                format.replace('-', "_")
             );
-            let expected = age_dir.join("rustdoc-api.json");
+            let expected = age_dir.join(format!("rustdoc-api{}.json", format));
             fs_snapshot::Test {
                 name,
                 kind: "".into(),
@@ -34,7 +45,7 @@ fn main() {
             }
         },
         move |input_path| {
-            let input = std::fs::read_to_string(&input_path).map_err(|e| e.to_string())?;
+            let input = std::fs::read_to_string(input_path).map_err(|e| e.to_string())?;
             let mut actual =
                 crate_api::rustdoc::parse_raw(&input, input_path).map_err(|e| e.to_string())?;
 
@@ -43,15 +54,15 @@ fn main() {
                 .manifest_path(&manifest_path)
                 .exec()
                 .map_err(|e| e.to_string())?;
-            let root_id = metadata.resolve.unwrap().root.unwrap();
+            let root_id = metadata.resolve.as_ref().unwrap().root.clone().unwrap();
             let pkg = metadata.packages.iter().find(|p| p.id == root_id).unwrap();
-            crate_api::manifest::Manifest::from(pkg).into_api(&mut actual);
+            crate_api::manifest::Manifest::from_metadata(&metadata, pkg).into_api(&mut actual);
 
             let actual = serde_json::to_string_pretty(&actual).map_err(|e| e.to_string())?;
             Ok(actual)
         },
     )
-    .select(["rustdoc-raw.json"])
+    .select(["rustdoc-raw.json", "rustdoc-raw-v57.json"])
     .overwrite(action == Action::Overwrite)
     .test()
 }
@@ -0,0 +1,104 @@
+fn package(manifest: &str, lib: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Cargo.toml"), manifest).unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/lib.rs"), lib).unwrap();
+    dir
+}
+
+const MANIFEST: &str = r#"[package]
+name = "errors"
+version = "0.1.0"
+edition = "2021"
+"#;
+
+fn nightly() -> crate_api::RustDocBuilder {
+    crate_api::RustDocBuilder::new().toolchain("nightly".to_owned())
+}
+
+fn dump_raw(dir: &tempfile::TempDir, builder: crate_api::RustDocBuilder) -> crate_api::Error {
+    builder
+        .target_directory(dir.path().join("target"))
+        .dump_raw(&dir.path().join("Cargo.toml"))
+        .unwrap_err()
+}
+
+fn command_stderr(err: &crate_api::Error) -> &str {
+    std::error::Error::source(err)
+        .and_then(|source| source.downcast_ref::<crate_api::CommandError>())
+        .unwrap()
+        .stderr()
+}
+
+#[test]
+fn missing_toolchain() {
+    let dir = package(MANIFEST, "");
+    let err = dump_raw(
+        &dir,
+        crate_api::RustDocBuilder::new().toolchain("crate-api-missing-toolchain".to_owned()),
+    );
+    assert_eq!(err.kind(), crate_api::ErrorKind::Toolchain, "{}", err);
+}
+
+#[test]
+fn unparseable_manifest() {
+    let dir = package("[package", "");
+    let err = dump_raw(&dir, nightly());
+    assert_eq!(err.kind(), crate_api::ErrorKind::InvalidManifest, "{}", err);
+}
+
+#[test]
+fn invalid_manifest_from_cargo() {
+    let manifest = format!(
+        "{}\n[dependencies]\nmissing = {{ path = \"missing\" }}\n",
+        MANIFEST
+    );
+    let dir = package(&manifest, "");
+    // Not silenced, so cargo's stderr is also written to ours
+    let err = dump_raw(&dir, nightly());
+    assert_eq!(err.kind(), crate_api::ErrorKind::InvalidManifest, "{}", err);
+    assert!(
+        command_stderr(&err).contains("missing"),
+        "{}",
+        command_stderr(&err)
+    );
+}
+
+#[test]
+fn virtual_manifest() {
+    let dir = package("[workspace]\nmembers = []\n", "");
+    let err = dump_raw(&dir, nightly());
+    assert_eq!(err.kind(), crate_api::ErrorKind::VirtualManifest, "{}", err);
+}
+
+#[test]
+fn compile_error() {
+    let dir = package(MANIFEST, "pub fn unclosed(");
+    let err = dump_raw(&dir, nightly());
+    assert_eq!(err.kind(), crate_api::ErrorKind::CompileError, "{}", err);
+    assert!(
+        command_stderr(&err).contains("could not document"),
+        "{}",
+        command_stderr(&err)
+    );
+}
+
+#[test]
+fn compile_error_silenced() {
+    let dir = package(MANIFEST, "pub fn unclosed(");
+    let err = dump_raw(&dir, nightly().silence(true));
+    assert_eq!(err.kind(), crate_api::ErrorKind::CompileError, "{}", err);
+    assert!(err.to_string().contains("could not document"), "{}", err);
+}
+
+#[test]
+fn missing_revision() {
+    let target_dir = tempfile::tempdir().unwrap();
+    let err = crate_api::Worktree::checkout(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+        "crate-api-missing-revision",
+        target_dir.path(),
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), crate_api::ErrorKind::Git, "{}", err);
+}
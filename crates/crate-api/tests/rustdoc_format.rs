@@ -0,0 +1,63 @@
+const FIXTURE: &str = "../../fixtures/dep_upgrade/new/rustdoc-raw.json";
+const FIXTURE_V57: &str = "../../fixtures/dep_upgrade/new/rustdoc-raw-v57.json";
+
+#[test]
+fn supported_format_round_trips() {
+    let raw = std::fs::read_to_string(FIXTURE).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    assert!(crate_api::rustdoc::SUPPORTED_FORMAT_VERSIONS
+        .contains(&(expected["format_version"].as_u64().unwrap() as u32)));
+
+    let parsed = crate_api::rustdoc::parse_raw_crate(&raw, std::path::Path::new(FIXTURE)).unwrap();
+    let actual = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn newer_format_is_rewritten() {
+    let raw = std::fs::read_to_string(FIXTURE_V57).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    assert!(crate_api::rustdoc::SUPPORTED_FORMAT_VERSIONS
+        .contains(&(expected["format_version"].as_u64().unwrap() as u32)));
+
+    let parsed =
+        crate_api::rustdoc::parse_raw_crate(&raw, std::path::Path::new(FIXTURE_V57)).unwrap();
+    assert_eq!(
+        parsed.format_version,
+        rustdoc_json_types_fork::FORMAT_VERSION
+    );
+    assert_eq!(
+        parsed.index.len(),
+        expected["index"].as_object().unwrap().len()
+    );
+    assert_eq!(
+        parsed.paths.len(),
+        expected["paths"].as_object().unwrap().len()
+    );
+}
+
+#[test]
+fn unsupported_format_is_rejected() {
+    let raw = std::fs::read_to_string(FIXTURE).unwrap();
+    let raw: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    let newest = *crate_api::rustdoc::SUPPORTED_FORMAT_VERSIONS
+        .iter()
+        .max()
+        .unwrap();
+    // Formats between the supported ones are as unknown as newer ones
+    for unsupported in [rustdoc_json_types_fork::FORMAT_VERSION + 1, newest + 1] {
+        let mut raw = raw.clone();
+        raw["format_version"] = serde_json::Value::from(unsupported);
+        let raw = serde_json::to_string(&raw).unwrap();
+
+        let err =
+            crate_api::rustdoc::parse_raw_crate(&raw, std::path::Path::new(FIXTURE)).unwrap_err();
+        assert_eq!(err.kind(), crate_api::ErrorKind::UnsupportedFormatVersion);
+    }
+}
+
+#[test]
+fn missing_format_is_a_parse_error() {
+    let err = crate_api::rustdoc::parse_raw_crate("{}", std::path::Path::new(FIXTURE)).unwrap_err();
+    assert_eq!(err.kind(), crate_api::ErrorKind::ApiParse);
+}
@@ -0,0 +1,3 @@
+pub use dep_upgrade_dep::Used;
+
+pub fn in_func(_var: dep2::InFunc) {}